@@ -0,0 +1,13 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum AmmError {
+    #[msg("Both sides of the pool must be funded")]
+    EmptyDeposit,
+    #[msg("Swap amount must be positive")]
+    ZeroAmount,
+    #[msg("Slippage tolerance exceeded")]
+    SlippageExceeded,
+    #[msg("Math overflow")]
+    MathOverflow,
+}
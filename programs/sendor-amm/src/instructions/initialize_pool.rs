@@ -0,0 +1,146 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{self, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked};
+use crate::errors::AmmError;
+use crate::math;
+use crate::state::{Pool, LP_DECIMALS, LP_MINT_SEED, POOL_SEED, SOL_VAULT_SEED};
+use crate::token;
+
+#[event]
+pub struct PoolInitialized {
+    pub pool: Pubkey,
+    pub creator: Pubkey,
+    pub token_mint: Pubkey,
+    pub sol_reserves: u64,
+    pub token_reserves: u64,
+    pub liquidity: u64,
+}
+
+#[derive(Accounts)]
+pub struct InitializePool<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = Pool::LEN,
+        seeds = [POOL_SEED, token_mint.key().as_ref(), creator.key().as_ref()],
+        bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Deposits the tokens and receives the LP tokens
+    pub creator: Signer<'info>,
+
+    /// Funded with the SOL side before this instruction runs
+    #[account(mut, seeds = [SOL_VAULT_SEED, pool.key().as_ref()], bump)]
+    pub sol_vault: SystemAccount<'info>,
+
+    #[account(mint::token_program = token_program)]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = token_mint,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program
+    )]
+    pub token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = token_mint, token::authority = creator)]
+    pub creator_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [LP_MINT_SEED, pool.key().as_ref()],
+        bump,
+        mint::decimals = LP_DECIMALS,
+        mint::authority = pool,
+        mint::token_program = lp_token_program
+    )]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = lp_mint,
+        associated_token::authority = creator,
+        associated_token::token_program = lp_token_program
+    )]
+    pub creator_lp_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub lp_token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Opens a pool with the SOL already sitting in its vault and `token_amount`
+/// tokens from the creator, minting the opening liquidity to the creator.
+/// Remaining accounts are forwarded to the mint's transfer hook.
+pub(crate) fn initialize_pool<'info>(
+    ctx: Context<'_, '_, '_, 'info, InitializePool<'info>>,
+    token_amount: u64,
+) -> Result<()> {
+    let accounts = ctx.accounts;
+    let sol_reserves = Pool::sol_reserves(&accounts.sol_vault)?;
+    require!(sol_reserves > 0 && token_amount > 0, AmmError::EmptyDeposit);
+
+    token::transfer_checked(
+        CpiContext::new(
+            accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: accounts.creator_token_account.to_account_info(),
+                mint: accounts.token_mint.to_account_info(),
+                to: accounts.token_vault.to_account_info(),
+                authority: accounts.creator.to_account_info(),
+            },
+        )
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+        token_amount,
+        accounts.token_mint.decimals,
+    )?;
+    // Transfer fees may have kept part of the deposit.
+    accounts.token_vault.reload()?;
+    let token_reserves = accounts.token_vault.amount;
+
+    let liquidity = math::initial_liquidity(sol_reserves, token_reserves);
+    require!(liquidity > 0, AmmError::EmptyDeposit);
+
+    let pool = &mut accounts.pool;
+    pool.creator = accounts.creator.key();
+    pool.token_mint = accounts.token_mint.key();
+    pool.token_vault = accounts.token_vault.key();
+    pool.lp_mint = accounts.lp_mint.key();
+    pool.bump = ctx.bumps.pool;
+    pool.sol_vault_bump = ctx.bumps.sol_vault;
+
+    let mint_key = pool.token_mint;
+    let seeds: &[&[u8]] = &[POOL_SEED, mint_key.as_ref(), pool.creator.as_ref(), &[pool.bump]];
+    token_interface::mint_to(
+        CpiContext::new_with_signer(
+            accounts.lp_token_program.to_account_info(),
+            MintTo {
+                mint: accounts.lp_mint.to_account_info(),
+                to: accounts.creator_lp_account.to_account_info(),
+                authority: pool.to_account_info(),
+            },
+            &[seeds],
+        ),
+        liquidity,
+    )?;
+
+    emit!(PoolInitialized {
+        pool: pool.key(),
+        creator: pool.creator,
+        token_mint: mint_key,
+        sol_reserves,
+        token_reserves,
+        liquidity,
+    });
+    Ok(())
+}
@@ -0,0 +1,148 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::errors::AmmError;
+use crate::math;
+use crate::state::{Pool, POOL_SEED, SOL_VAULT_SEED};
+use crate::token;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SwapDirection {
+    SolToToken,
+    TokenToSol,
+}
+
+#[event]
+pub struct Swapped {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub direction: SwapDirection,
+    pub amount_in: u64,
+    pub amount_out: u64,
+}
+
+#[derive(Accounts)]
+pub struct Swap<'info> {
+    #[account(has_one = token_mint, has_one = token_vault)]
+    pub pool: Account<'info, Pool>,
+
+    #[account(mut, seeds = [SOL_VAULT_SEED, pool.key().as_ref()], bump = pool.sol_vault_bump)]
+    pub sol_vault: SystemAccount<'info>,
+
+    #[account(mint::token_program = token_program)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(mut, token::mint = token_mint, token::authority = user)]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Trades `amount_in` of one side for at least `min_amount_out` of the other.
+/// Token inputs are priced on what reaches the vault after any transfer fee.
+/// Remaining accounts are forwarded to the mint's transfer hook.
+pub(crate) fn swap<'info>(
+    ctx: Context<'_, '_, '_, 'info, Swap<'info>>,
+    direction: SwapDirection,
+    amount_in: u64,
+    min_amount_out: u64,
+) -> Result<()> {
+    require!(amount_in > 0, AmmError::ZeroAmount);
+    let accounts = ctx.accounts;
+    let sol_reserves = Pool::sol_reserves(&accounts.sol_vault)?;
+    let token_reserves = accounts.token_vault.amount;
+    let decimals = accounts.token_mint.decimals;
+
+    let amount_out = match direction {
+        SwapDirection::SolToToken => {
+            let out = math::swap_out(sol_reserves, token_reserves, amount_in)
+                .ok_or(AmmError::MathOverflow)?;
+            require!(out >= min_amount_out, AmmError::SlippageExceeded);
+
+            system_program::transfer(
+                CpiContext::new(
+                    accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: accounts.user.to_account_info(),
+                        to: accounts.sol_vault.to_account_info(),
+                    },
+                ),
+                amount_in,
+            )?;
+
+            let pool = &accounts.pool;
+            let seeds: &[&[u8]] =
+                &[POOL_SEED, pool.token_mint.as_ref(), pool.creator.as_ref(), &[pool.bump]];
+            token::transfer_checked(
+                CpiContext::new_with_signer(
+                    accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: accounts.token_vault.to_account_info(),
+                        mint: accounts.token_mint.to_account_info(),
+                        to: accounts.user_token_account.to_account_info(),
+                        authority: pool.to_account_info(),
+                    },
+                    &[seeds],
+                )
+                .with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+                out,
+                decimals,
+            )?;
+            out
+        }
+        SwapDirection::TokenToSol => {
+            token::transfer_checked(
+                CpiContext::new(
+                    accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: accounts.user_token_account.to_account_info(),
+                        mint: accounts.token_mint.to_account_info(),
+                        to: accounts.token_vault.to_account_info(),
+                        authority: accounts.user.to_account_info(),
+                    },
+                )
+                .with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+                amount_in,
+                decimals,
+            )?;
+            accounts.token_vault.reload()?;
+            let received = accounts.token_vault.amount - token_reserves;
+
+            let out = math::swap_out(token_reserves, sol_reserves, received)
+                .ok_or(AmmError::MathOverflow)?;
+            require!(out >= min_amount_out, AmmError::SlippageExceeded);
+
+            let pool_key = accounts.pool.key();
+            let seeds: &[&[u8]] =
+                &[SOL_VAULT_SEED, pool_key.as_ref(), &[accounts.pool.sol_vault_bump]];
+            system_program::transfer(
+                CpiContext::new_with_signer(
+                    accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: accounts.sol_vault.to_account_info(),
+                        to: accounts.user.to_account_info(),
+                    },
+                    &[seeds],
+                ),
+                out,
+            )?;
+            out
+        }
+    };
+
+    emit!(Swapped {
+        pool: accounts.pool.key(),
+        user: accounts.user.key(),
+        direction,
+        amount_in,
+        amount_out,
+    });
+    Ok(())
+}
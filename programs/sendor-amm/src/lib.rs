@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+declare_id!("68hxvYR1KtREJktSMBs2MdGfV94MvEdedAqYQyREBfwr");
+
+pub mod errors;
+pub mod math;
+pub mod state;
+pub mod token;
+pub mod instructions;
+
+pub use instructions::initialize_pool::*;
+pub use instructions::swap::*;
+
+/// Constant-product SOL/token pools. Sendor launches graduate into one of
+/// these once their curve completes; the pool works like any other x * y = k
+/// market and runs on localnet alongside the launchpad.
+#[program]
+pub mod sendor_amm {
+    use super::*;
+    use crate::instructions::{initialize_pool, swap};
+
+    pub fn initialize_pool<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitializePool<'info>>,
+        token_amount: u64,
+    ) -> Result<()> {
+        initialize_pool::initialize_pool(ctx, token_amount)
+    }
+
+    pub fn swap<'info>(
+        ctx: Context<'_, '_, '_, 'info, Swap<'info>>,
+        direction: SwapDirection,
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        swap::swap(ctx, direction, amount_in, min_amount_out)
+    }
+}
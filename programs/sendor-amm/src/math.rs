@@ -0,0 +1,64 @@
+//! Constant-product pricing.
+
+/// Fee kept in the pool on every swap, in basis points of the input.
+pub const SWAP_FEE_BPS: u64 = 30;
+const BPS_DENOMINATOR: u64 = 10_000;
+
+/// LP supply minted for the opening deposit: the geometric mean of the two
+/// sides, so the share price starts independent of either unit.
+pub fn initial_liquidity(sol: u64, tokens: u64) -> u64 {
+    isqrt(sol as u128 * tokens as u128) as u64
+}
+
+/// Output of swapping `amount_in` into a pool holding `reserve_in` and
+/// `reserve_out`, after the swap fee. Rounds down, so `k` never shrinks.
+pub fn swap_out(reserve_in: u64, reserve_out: u64, amount_in: u64) -> Option<u64> {
+    let fee = (amount_in as u128 * SWAP_FEE_BPS as u128).div_ceil(BPS_DENOMINATOR as u128);
+    let in_after_fee = amount_in as u128 - fee;
+    // u64 * u64 always fits in u128.
+    let numerator = in_after_fee * reserve_out as u128;
+    let denominator = reserve_in as u128 + in_after_fee;
+    u64::try_from(numerator.checked_div(denominator)?).ok()
+}
+
+fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    let mut x = 1u128 << (n.ilog2() / 2 + 1);
+    loop {
+        let y = (x + n / x) / 2;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn isqrt_is_the_floor_root(n in any::<u128>()) {
+            let r = isqrt(n);
+            prop_assert!(r * r <= n);
+            prop_assert!((r + 1).checked_mul(r + 1).is_none_or(|sq| sq > n));
+        }
+
+        #[test]
+        fn swaps_never_shrink_the_product(
+            reserve_in in 1u64..=u64::MAX / 2,
+            reserve_out in 1u64..,
+            amount_in in 0u64..=u64::MAX / 2,
+        ) {
+            let out = swap_out(reserve_in, reserve_out, amount_in).unwrap();
+            prop_assert!(out < reserve_out);
+            let before = reserve_in as u128 * reserve_out as u128;
+            let after = (reserve_in + amount_in) as u128 * (reserve_out - out) as u128;
+            prop_assert!(after >= before);
+        }
+    }
+}
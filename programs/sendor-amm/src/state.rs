@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+pub const POOL_SEED: &[u8] = b"pool";
+pub const SOL_VAULT_SEED: &[u8] = b"pool_sol";
+pub const LP_MINT_SEED: &[u8] = b"lp_mint";
+pub const LP_DECIMALS: u8 = 9;
+
+/// A SOL/token pool. SOL sits in a system-owned vault PDA and tokens in the
+/// pool's associated token account; reserves are read from their balances.
+#[account]
+#[derive(InitSpace)]
+pub struct Pool {
+    /// Opened the pool; part of its seeds, so each creator gets one pool per mint
+    pub creator: Pubkey,
+    pub token_mint: Pubkey,
+    pub token_vault: Pubkey,
+    pub lp_mint: Pubkey,
+    pub bump: u8,
+    pub sol_vault_bump: u8,
+}
+
+impl Pool {
+    /// 8-byte discriminator + fields
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+
+    /// SOL available to trade, leaving the vault rent-exempt.
+    pub fn sol_reserves(sol_vault: &AccountInfo) -> Result<u64> {
+        Ok(sol_vault.lamports().saturating_sub(Rent::get()?.minimum_balance(0)))
+    }
+}
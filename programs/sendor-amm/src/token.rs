@@ -0,0 +1,25 @@
+//! Token movements for mints that may carry a Token-2022 transfer hook.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{spl_token_2022, TransferChecked};
+
+/// `transfer_checked` that forwards `ctx.remaining_accounts` to the mint's
+/// transfer hook, if it has one.
+pub fn transfer_checked<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, TransferChecked<'info>>,
+    amount: u64,
+    decimals: u8,
+) -> Result<()> {
+    spl_token_2022::onchain::invoke_transfer_checked(
+        ctx.program.key,
+        ctx.accounts.from,
+        ctx.accounts.mint,
+        ctx.accounts.to,
+        ctx.accounts.authority,
+        &ctx.remaining_accounts,
+        amount,
+        decimals,
+        ctx.signer_seeds,
+    )
+    .map_err(Into::into)
+}
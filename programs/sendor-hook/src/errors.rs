@@ -0,0 +1,19 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum HookError {
+    #[msg("Hook invoked outside a token transfer")]
+    NotTransferring,
+    #[msg("Launch accounts do not match the mint")]
+    LaunchMismatch,
+    #[msg("Sender has no transfer record; open one with init_transfer_record or send through sendor")]
+    MissingTransferRecord,
+    #[msg("Launch is paused")]
+    Paused,
+    #[msg("Outside trading window")]
+    NotInTradingWindow,
+    #[msg("Action limit reached for the trailing 24 hours")]
+    ActionLimitReached,
+    #[msg("Exceeds transfer limit")]
+    ExceedsTransferLimit,
+}
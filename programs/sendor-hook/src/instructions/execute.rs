@@ -0,0 +1,118 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    spl_token_2022::{
+        extension::{transfer_hook::TransferHookAccount, BaseStateWithExtensions, StateWithExtensions},
+        state::Account as SplAccount,
+    },
+    Mint, TokenAccount,
+};
+use sendor::state::{
+    global_state::GlobalState, launch_metadata::LaunchMetadata, launch_policy::LaunchPolicy,
+    vesting::VESTING_SEED,
+};
+use sendor::transfer_hook::EXTRA_ACCOUNT_METAS_SEED;
+use crate::errors::HookError;
+use crate::state::{TransferRecord, RECORD_SEED};
+
+#[derive(Accounts)]
+pub struct Execute<'info> {
+    #[account(token::mint = mint)]
+    pub source_token: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(token::mint = mint)]
+    pub destination_token: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: owner or delegate signing the transfer; limits follow the owner
+    pub authority: UncheckedAccount<'info>,
+    /// CHECK: validation account, read by the token program
+    #[account(seeds = [EXTRA_ACCOUNT_METAS_SEED, mint.key().as_ref()], bump)]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+    /// CHECK: sendor launch; closed once the launch migrates
+    pub launch_metadata: UncheckedAccount<'info>,
+    /// CHECK: sendor launch policy
+    pub launch_policy: UncheckedAccount<'info>,
+    /// CHECK: sendor global state
+    pub global_state: UncheckedAccount<'info>,
+    /// CHECK: the source owner's record, only loaded for limited transfers
+    #[account(mut, seeds = [RECORD_SEED, mint.key().as_ref(), source_token.owner.as_ref()], bump)]
+    pub transfer_record: UncheckedAccount<'info>,
+}
+
+/// Applies the launch's window and transfer limit to a transfer that has
+/// already moved `amount` out of the source account. Vault transfers are buys,
+/// sells and migration, which sendor limits itself, and claims from a
+/// holder's own vesting schedule follow that schedule instead.
+pub(crate) fn execute(ctx: Context<Execute>, amount: u64) -> Result<()> {
+    let accounts = &ctx.accounts;
+    check_transferring(&accounts.source_token)?;
+
+    // A graduated launch is closed and its token trades freely.
+    let Some(launch) = load::<LaunchMetadata>(&accounts.launch_metadata)? else {
+        return Ok(());
+    };
+    require_keys_eq!(launch.token_mint, accounts.mint.key(), HookError::LaunchMismatch);
+    let vault = launch.vault;
+    if accounts.source_token.key() == vault || accounts.destination_token.key() == vault {
+        return Ok(());
+    }
+    let (schedule, _) = Pubkey::find_program_address(
+        &[
+            VESTING_SEED,
+            accounts.launch_metadata.key().as_ref(),
+            accounts.destination_token.owner.as_ref(),
+        ],
+        &sendor::ID,
+    );
+    if accounts.source_token.owner == schedule {
+        return Ok(());
+    }
+
+    let policy = load::<LaunchPolicy>(&accounts.launch_policy)?.ok_or(HookError::LaunchMismatch)?;
+    require_keys_eq!(
+        policy.launch_metadata,
+        accounts.launch_metadata.key(),
+        HookError::LaunchMismatch
+    );
+    let global = load::<GlobalState>(&accounts.global_state)?.ok_or(HookError::LaunchMismatch)?;
+    require!(!launch.is_paused(&global), HookError::Paused);
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(policy.schedule.is_open(now), HookError::NotInTradingWindow);
+
+    // The hook gets no signer to pay for a record, and one it cannot write
+    // would let every transfer start from an empty window, so it has to exist.
+    // Sendor's `transfer` opens it on first use.
+    let record_info = accounts.transfer_record.to_account_info();
+    let mut record = load::<TransferRecord>(&accounts.transfer_record)?
+        .ok_or(HookError::MissingTransferRecord)?;
+
+    // The token program has already debited the source.
+    let balance = accounts.source_token.amount.saturating_add(amount);
+    let limit = record.limit.at(now, balance);
+    require!(limit.action_count() < policy.actions_per_day, HookError::ActionLimitReached);
+    let allowance = limit.remaining(policy.max_transfer(limit.snapshot_balance)?);
+    require!(amount <= allowance, HookError::ExceedsTransferLimit);
+
+    record.limit.record(now, balance, amount)?;
+    record.try_serialize(&mut &mut record_info.try_borrow_mut_data()?[..])?;
+    Ok(())
+}
+
+/// Rejects direct calls: the flag is only set while the token program is
+/// running this hook for a real transfer.
+fn check_transferring(source: &InterfaceAccount<TokenAccount>) -> Result<()> {
+    let info = source.to_account_info();
+    let data = info.try_borrow_data()?;
+    let account = StateWithExtensions::<SplAccount>::unpack(&data)?;
+    let extension = account.get_extension::<TransferHookAccount>()?;
+    require!(bool::from(extension.transferring), HookError::NotTransferring);
+    Ok(())
+}
+
+/// Deserializes `info` if it is a live account of type `T`.
+fn load<T: AccountDeserialize + Owner>(info: &AccountInfo) -> Result<Option<T>> {
+    if info.owner != &T::owner() || info.data_is_empty() {
+        return Ok(None);
+    }
+    let data = info.try_borrow_data()?;
+    T::try_deserialize(&mut &data[..]).map(Some)
+}
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use sendor::state::user_record::RollingLimit;
+use crate::state::{TransferRecord, RECORD_SEED};
+
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct InitTransferRecord<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = TransferRecord::LEN,
+        seeds = [RECORD_SEED, mint.key().as_ref(), owner.as_ref()],
+        bump
+    )]
+    pub transfer_record: Account<'info, TransferRecord>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Opens `owner`'s record for `mint`. Anyone may pay for it; the record only
+/// ever restricts its owner.
+pub(crate) fn init_transfer_record(ctx: Context<InitTransferRecord>, owner: Pubkey) -> Result<()> {
+    let record = &mut ctx.accounts.transfer_record;
+    record.owner = owner;
+    record.mint = ctx.accounts.mint.key();
+    record.limit = RollingLimit::default();
+    record.bump = ctx.bumps.transfer_record;
+    Ok(())
+}
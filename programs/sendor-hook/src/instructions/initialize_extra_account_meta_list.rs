@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use sendor::transfer_hook::EXTRA_ACCOUNT_METAS_SEED;
+use spl_tlv_account_resolution::{account::ExtraAccountMeta, seeds::Seed, state::ExtraAccountMetaList};
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+use crate::errors::HookError;
+use crate::state::RECORD_SEED;
+
+/// Accounts `execute` needs beyond the standard five.
+const EXTRA_ACCOUNTS: usize = 4;
+
+#[derive(Accounts)]
+pub struct InitializeExtraAccountMetaList<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: TLV validation account read by the token program
+    #[account(
+        init,
+        payer = payer,
+        space = ExtraAccountMetaList::size_of(EXTRA_ACCOUNTS)?,
+        seeds = [EXTRA_ACCOUNT_METAS_SEED, mint.key().as_ref()],
+        bump
+    )]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// The launch PDA signs as mint authority, so only sendor can register a
+    /// list and it vouches for the accounts below.
+    #[account(
+        constraint = mint.mint_authority == Some(launch_metadata.key()).into() @ HookError::LaunchMismatch
+    )]
+    pub launch_metadata: Signer<'info>,
+
+    /// CHECK: vouched for by the launch signer
+    pub launch_policy: UncheckedAccount<'info>,
+
+    /// CHECK: sendor's global state, pinned by its seeds
+    #[account(seeds = [b"global_v2"], seeds::program = sendor::ID, bump)]
+    pub global_state: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn initialize_extra_account_meta_list(
+    ctx: Context<InitializeExtraAccountMetaList>,
+) -> Result<()> {
+    // Indices 0-4 are source, mint, destination, authority and this list.
+    let metas = [
+        ExtraAccountMeta::new_with_pubkey(&ctx.accounts.launch_metadata.key(), false, false)?,
+        ExtraAccountMeta::new_with_pubkey(&ctx.accounts.launch_policy.key(), false, false)?,
+        ExtraAccountMeta::new_with_pubkey(&ctx.accounts.global_state.key(), false, false)?,
+        // Keyed on the source account's owner (bytes 32..64) rather than the
+        // signing authority, so delegates cannot sidestep the owner's limit.
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: RECORD_SEED.to_vec() },
+                Seed::AccountKey { index: 1 },
+                Seed::AccountData { account_index: 0, data_index: 32, length: 32 },
+            ],
+            false,
+            true,
+        )?,
+    ];
+
+    let info = ctx.accounts.extra_account_meta_list.to_account_info();
+    let mut data = info.try_borrow_mut_data()?;
+    ExtraAccountMetaList::init::<ExecuteInstruction>(&mut data, &metas)?;
+    Ok(())
+}
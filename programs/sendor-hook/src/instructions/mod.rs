@@ -0,0 +1,3 @@
+pub mod initialize_extra_account_meta_list;
+pub mod init_transfer_record;
+pub mod execute;
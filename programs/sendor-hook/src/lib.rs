@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+use spl_discriminator::SplDiscriminate;
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+
+declare_id!("J5EzMNEkgUcnyaAQkrUyPkYhbNj9pXnQPXLLvqm2cwv7");
+
+pub mod errors;
+pub mod state;
+pub mod instructions;
+
+pub use instructions::initialize_extra_account_meta_list::*;
+pub use instructions::init_transfer_record::*;
+pub use instructions::execute::*;
+
+/// Token-2022 transfer hook for sendor launches. Holders can move tokens with
+/// the token program directly, so the window and transfer-limit rules are
+/// enforced here on every transfer, not only through sendor's `transfer`.
+#[program]
+pub mod sendor_hook {
+    use super::*;
+    use crate::instructions::{execute, init_transfer_record, initialize_extra_account_meta_list};
+
+    pub fn initialize_extra_account_meta_list(
+        ctx: Context<InitializeExtraAccountMetaList>,
+    ) -> Result<()> {
+        initialize_extra_account_meta_list::initialize_extra_account_meta_list(ctx)
+    }
+
+    pub fn init_transfer_record(ctx: Context<InitTransferRecord>, owner: Pubkey) -> Result<()> {
+        init_transfer_record::init_transfer_record(ctx, owner)
+    }
+
+    #[instruction(discriminator = ExecuteInstruction::SPL_DISCRIMINATOR_SLICE)]
+    pub fn execute(ctx: Context<Execute>, amount: u64) -> Result<()> {
+        execute::execute(ctx, amount)
+    }
+}
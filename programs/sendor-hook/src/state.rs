@@ -0,0 +1,22 @@
+use anchor_lang::prelude::*;
+use sendor::state::user_record::RollingLimit;
+
+pub const RECORD_SEED: &[u8] = b"record";
+
+/// A holder's transfers of one mint. Sendor's `UserRecord` belongs to the
+/// sendor program and the hook cannot call back into it mid-transfer, so the
+/// hook keeps its own allowance with the same rules. Sendor reads `limit` at
+/// its fixed offset, so the field order must not change.
+#[account]
+#[derive(InitSpace)]
+pub struct TransferRecord {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub limit: RollingLimit,
+    pub bump: u8,
+}
+
+impl TransferRecord {
+    /// 8-byte discriminator + fields
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+}
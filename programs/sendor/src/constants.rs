@@ -1,12 +1,26 @@
 use anchor_lang::prelude::*;
 
-
 pub const TOKEN_DECIMALS: u8 = 9;                        // 9 decimal places for SPL token
 pub const INITIAL_SUPPLY_TOKENS: u64 = 1_000_000_000;    // 1 billion tokens (whole tokens)
 pub const INITIAL_SUPPLY_BASE_UNITS: u64 = INITIAL_SUPPLY_TOKENS * 1_000_000_000;  // 1e9 * 1e9 = 1e18 base units
 
+pub const MAX_TOKEN_NAME_LEN: usize = 32;      // bytes
+pub const MAX_TOKEN_SYMBOL_LEN: usize = 10;    // bytes
+pub const MAX_TOKEN_URI_LEN: usize = 200;      // bytes
+
 pub const SELL_LIMIT_PERCENT: u64 = 10;      // 10% sell limit per day
 pub const TRANSFER_LIMIT_PERCENT: u64 = 20;  // 20% transfer limit per day
 
-pub const WINDOW_DURATION: i64 = 15 * 60;    // 15 minutes window duration (in seconds)
-pub const HALF_DAY: i64 = 12 * 60 * 60;      // 12 hours in seconds (half-day interval)
+pub const WINDOW_DURATION: i64 = 15 * 60;    // default trading window length (in seconds)
+pub const HALF_DAY: i64 = 12 * 60 * 60;      // default spacing between window starts
+pub const EMERGENCY_EXIT_DELAY: i64 = 3 * 24 * 60 * 60; // pause length after which holders may exit
+pub const RANDOMNESS_FALLBACK_DELAY: i64 = 4 * 60 * 60; // time into a UTC day without a reveal before windows use the last value
+
+pub const BPS_DENOMINATOR: u64 = 10_000;      // basis points in 100%
+pub const MAX_TRADE_FEE_BPS: u64 = 1_000;    // trading fees may never exceed 10% per side
+pub const MAX_TRANSFER_FEE_BPS: u16 = 500;   // Token-2022 transfer fees may never exceed 5%
+
+pub const MAX_VESTING_ALLOCATIONS: usize = 4; // vesting schedules per launch
+pub const MAX_VESTED_SUPPLY_BPS: u64 = 2_000; // at most 20% of supply may be held back from the curve
+
+pub const TRANSFER_HOOK_PROGRAM_ID: Pubkey = pubkey!("J5EzMNEkgUcnyaAQkrUyPkYhbNj9pXnQPXLLvqm2cwv7"); // sendor-hook, enforces limits on direct transfers
@@ -0,0 +1,524 @@
+//! Bonding-curve pricing shared by every instruction that quotes a trade.
+//!
+//! Each curve family only defines `integral(x)`: the cumulative cost of the
+//! first `x` base units, in lamports scaled by `10^decimals`, rounded down and
+//! non-decreasing in `x`. Every quote is a difference of two integrals:
+//!
+//! * `cost_to_buy(s, q)    = ceil ((F(s + q) - F(s)) / 10^decimals)`
+//! * `payout_to_sell(s, q) = floor((F(s) - F(s - q)) / 10^decimals)`
+//!
+//! Selling `q` right after buying `q` prices the same range `[s, s + q]`, so
+//! the payout is the floor of the exact amount the buyer paid the ceiling of
+//! and can never exceed it. Because `F` telescopes, splitting the sell into
+//! chunks only adds more floors, and splitting the buy only adds more ceilings.
+
+use anchor_lang::prelude::*;
+use crate::constants::BPS_DENOMINATOR;
+use crate::errors::LaunchError;
+use crate::state::curve_kind::CurveKind;
+
+/// Fixed-point scale used for exponential growth factors.
+const GROWTH_SCALE: u128 = 1_000_000_000_000;
+
+/// Common pricing interface implemented by every curve family.
+pub trait PricingCurve {
+    /// Lamports required to buy `amount` base units when `supply` are already sold.
+    fn cost_to_buy(&self, supply: u64, amount: u64, decimals: u8) -> Result<u64>;
+    /// Lamports paid out for selling `amount` base units back at `supply`.
+    fn payout_to_sell(&self, supply: u64, amount: u64, decimals: u8) -> Result<u64>;
+    /// Marginal price in lamports per whole token at `supply`.
+    fn spot_price(&self, supply: u64, decimals: u8) -> Result<u64>;
+    /// Largest amount, at most `cap`, whose `cost_to_buy` fits in `lamports`.
+    fn max_buy_for(&self, supply: u64, lamports: u64, cap: u64, decimals: u8) -> Result<u64>;
+    /// Smallest amount whose `payout_to_sell` reaches `lamports`.
+    fn min_sell_for(&self, supply: u64, lamports: u64, decimals: u8) -> Result<u64>;
+}
+
+impl PricingCurve for CurveKind {
+    fn cost_to_buy(&self, supply: u64, amount: u64, decimals: u8) -> Result<u64> {
+        let end = supply.checked_add(amount).ok_or(LaunchError::MathOverflow)?;
+        let m = unit(decimals)?;
+        to_lamports(self.area(supply, end, m)?.div_ceil(m))
+    }
+
+    fn payout_to_sell(&self, supply: u64, amount: u64, decimals: u8) -> Result<u64> {
+        let start = supply.checked_sub(amount).ok_or(LaunchError::MathOverflow)?;
+        let m = unit(decimals)?;
+        to_lamports(self.area(start, supply, m)? / m)
+    }
+
+    fn spot_price(&self, supply: u64, decimals: u8) -> Result<u64> {
+        let m = unit(decimals)?;
+        let x = supply as u128;
+        let price = match *self {
+            CurveKind::Linear { base_price, slope } => {
+                (base_price as u128)
+                    .checked_add(mul_div(slope as u128, x, m)?)
+                    .ok_or(LaunchError::MathOverflow)?
+            }
+            CurveKind::Exponential { base_price, growth_bps, step } => {
+                let factor = growth_power(growth_bps, x / step as u128)?;
+                mul_div(base_price as u128, factor, GROWTH_SCALE)?
+            }
+            CurveKind::ConstantProduct { virtual_sol_reserves, virtual_token_reserves } => {
+                let vt = virtual_token_reserves as u128;
+                require!(x < vt, LaunchError::InsufficientSupply);
+                let tokens = vt - x;
+                let k = virtual_sol_reserves as u128 * vt;
+                mul_div(k, m, tokens)? / tokens
+            }
+            CurveKind::PiecewiseLinear { points, len } => {
+                let points = &points[..len as usize];
+                let mut price = points[points.len() - 1].price as u128;
+                for pair in points.windows(2) {
+                    let (lo, hi) = (pair[0], pair[1]);
+                    if x < hi.supply as u128 {
+                        let seg = (hi.supply - lo.supply) as u128;
+                        let dx = x - lo.supply as u128;
+                        price = lo.price as u128 + mul_div((hi.price - lo.price) as u128, dx, seg)?;
+                        break;
+                    }
+                }
+                price
+            }
+        };
+        to_lamports(price)
+    }
+
+    fn max_buy_for(&self, supply: u64, lamports: u64, cap: u64, decimals: u8) -> Result<u64> {
+        let m = unit(decimals)?;
+        let start = self.integral(supply, m)?;
+        let hint = (lamports as u128)
+            .checked_mul(m)
+            .and_then(|budget| start.checked_add(budget))
+            .and_then(|target| self.inverse_estimate(target, m))
+            .and_then(|end| end.checked_sub(supply as u128));
+        let affordable =
+            |q: u128| matches!(self.cost_to_buy(supply, q as u64, decimals), Ok(c) if c <= lamports);
+        Ok(search_max(hint, cap as u128, affordable) as u64)
+    }
+
+    fn min_sell_for(&self, supply: u64, lamports: u64, decimals: u8) -> Result<u64> {
+        require!(lamports > 0, LaunchError::InvalidParams);
+        let m = unit(decimals)?;
+        let end = self.integral(supply, m)?;
+        let hint = (lamports as u128)
+            .checked_mul(m)
+            .and_then(|target| end.checked_sub(target))
+            .and_then(|target| self.inverse_estimate(target, m))
+            .and_then(|start| (supply as u128).checked_sub(start + 1));
+        let short =
+            |q: u128| matches!(self.payout_to_sell(supply, q as u64, decimals), Ok(p) if p < lamports);
+        let most_short = search_max(hint, supply as u128, short);
+        require!(most_short < supply as u128, LaunchError::InsufficientLiquidity);
+        let amount = most_short as u64 + 1;
+        // A quote that overflowed is not a sufficient one.
+        require!(
+            self.payout_to_sell(supply, amount, decimals)? >= lamports,
+            LaunchError::InsufficientLiquidity
+        );
+        Ok(amount)
+    }
+}
+
+impl CurveKind {
+    /// Sanity-checks the creator-supplied parameters for a curve that will
+    /// sell up to `max_supply` base units, and caps the final spot price.
+    pub fn validate(&self, max_supply: u64, decimals: u8, max_final_price: u64) -> Result<()> {
+        match *self {
+            CurveKind::Linear { base_price, slope } => {
+                require!(base_price > 0 && slope > 0, LaunchError::InvalidParams);
+            }
+            CurveKind::Exponential { base_price, growth_bps, step } => {
+                require!(base_price > 0 && growth_bps > 0 && step > 0, LaunchError::InvalidParams);
+            }
+            CurveKind::ConstantProduct { virtual_sol_reserves, virtual_token_reserves } => {
+                require!(virtual_sol_reserves > 0, LaunchError::InvalidParams);
+                require!(virtual_token_reserves > max_supply, LaunchError::InvalidParams);
+            }
+            CurveKind::PiecewiseLinear { points, len } => {
+                let len = len as usize;
+                require!((2..=points.len()).contains(&len), LaunchError::InvalidParams);
+                require!(points[0].supply == 0 && points[0].price > 0, LaunchError::InvalidParams);
+                for pair in points[..len].windows(2) {
+                    require!(pair[1].supply > pair[0].supply, LaunchError::InvalidParams);
+                    require!(pair[1].price >= pair[0].price, LaunchError::InvalidParams);
+                }
+            }
+        }
+        let final_price = self.spot_price(max_supply, decimals)?;
+        require!(final_price <= max_final_price, LaunchError::InvalidParams);
+        Ok(())
+    }
+
+    /// Closed-form guess at the supply `y` where `integral(y)` reaches
+    /// `target`, refined by `search_max`. `None` when the family has no cheap
+    /// inverse or the guess overflows.
+    fn inverse_estimate(&self, target: u128, m: u128) -> Option<u128> {
+        match *self {
+            CurveKind::Linear { base_price, slope } => {
+                // slope * y^2 / (2m) + base * y = target, solved for y
+                let (base, slope) = (base_price as u128, slope as u128);
+                let mb = m.checked_mul(base)?;
+                let disc = mb
+                    .checked_mul(mb)?
+                    .checked_add(slope.checked_mul(2 * m)?.checked_mul(target)?)?;
+                Some((isqrt(disc) - mb) / slope)
+            }
+            CurveKind::ConstantProduct { virtual_sol_reserves, virtual_token_reserves } => {
+                // k * m / (vt - y) = vs * m + target, solved for y
+                let vs = virtual_sol_reserves as u128;
+                let vt = virtual_token_reserves as u128;
+                let sol = target.checked_add(vs.checked_mul(m)?)?;
+                vt.checked_sub((vs * vt).checked_mul(m)? / sol + 1)
+            }
+            CurveKind::Exponential { .. } | CurveKind::PiecewiseLinear { .. } => None,
+        }
+    }
+
+    /// Scaled lamports between supply `from` and `to` (`from <= to`).
+    fn area(&self, from: u64, to: u64, m: u128) -> Result<u128> {
+        self.integral(to, m)?
+            .checked_sub(self.integral(from, m)?)
+            .ok_or(error!(LaunchError::MathOverflow))
+    }
+
+    /// Cumulative cost of the first `x` base units, in lamports scaled by `m`.
+    fn integral(&self, x: u64, m: u128) -> Result<u128> {
+        let x = x as u128;
+        match *self {
+            CurveKind::Linear { base_price, slope } => {
+                let flat = (base_price as u128).checked_mul(x).ok_or(LaunchError::MathOverflow)?;
+                let ramp = mul_div(slope as u128, x * x, 2 * m)?;
+                flat.checked_add(ramp).ok_or(error!(LaunchError::MathOverflow))
+            }
+            CurveKind::Exponential { base_price, growth_bps, step } => {
+                // Price is flat inside a step, so interpolate linearly between
+                // the closed-form step boundaries; this keeps F monotone even
+                // though the fixed-point growth factor is rounded.
+                let step = step as u128;
+                let n = x / step;
+                let lo = exponential_boundary(base_price, growth_bps, step, n)?;
+                let rem = x % step;
+                if rem == 0 {
+                    return Ok(lo);
+                }
+                let hi = exponential_boundary(base_price, growth_bps, step, n + 1)?;
+                let gap = hi.checked_sub(lo).ok_or(LaunchError::MathOverflow)?;
+                lo.checked_add(mul_div(gap, rem, step)?)
+                    .ok_or(error!(LaunchError::MathOverflow))
+            }
+            CurveKind::ConstantProduct { virtual_sol_reserves, virtual_token_reserves } => {
+                let vs = virtual_sol_reserves as u128;
+                let vt = virtual_token_reserves as u128;
+                require!(x < vt, LaunchError::InsufficientSupply);
+                // SOL reserve after x tokens left the pool, minus the starting reserve
+                let k = vs * vt;
+                let sol_after = mul_div(k, m, vt - x)?;
+                sol_after
+                    .checked_sub(vs.checked_mul(m).ok_or(LaunchError::MathOverflow)?)
+                    .ok_or(error!(LaunchError::MathOverflow))
+            }
+            CurveKind::PiecewiseLinear { points, len } => {
+                let points = &points[..len as usize];
+                let mut total: u128 = 0;
+                for pair in points.windows(2) {
+                    let (lo, hi) = (pair[0], pair[1]);
+                    if x <= lo.supply as u128 {
+                        return Ok(total);
+                    }
+                    let seg = (hi.supply - lo.supply) as u128;
+                    let dx = x.min(hi.supply as u128) - lo.supply as u128;
+                    let flat = (lo.price as u128).checked_mul(dx).ok_or(LaunchError::MathOverflow)?;
+                    let rise = mul_div((hi.price - lo.price) as u128, dx * dx, 2 * seg)?;
+                    total = total
+                        .checked_add(flat)
+                        .and_then(|t| t.checked_add(rise))
+                        .ok_or(LaunchError::MathOverflow)?;
+                }
+                let last = points[points.len() - 1];
+                let tail = x.saturating_sub(last.supply as u128);
+                let flat = (last.price as u128).checked_mul(tail).ok_or(LaunchError::MathOverflow)?;
+                total.checked_add(flat).ok_or(error!(LaunchError::MathOverflow))
+            }
+        }
+    }
+}
+
+/// base * step * (g^n - 1) / (g - 1): scaled cost of the first `n` full steps.
+fn exponential_boundary(base_price: u64, growth_bps: u16, step: u128, n: u128) -> Result<u128> {
+    let factor = growth_power(growth_bps, n)?;
+    let series = mul_div(factor - GROWTH_SCALE, BPS_DENOMINATOR as u128, growth_bps as u128)?;
+    let per_step = (base_price as u128).checked_mul(step).ok_or(LaunchError::MathOverflow)?;
+    mul_div(per_step, series, GROWTH_SCALE)
+}
+
+fn unit(decimals: u8) -> Result<u128> {
+    require!(decimals <= 18, LaunchError::InvalidDecimals);
+    Ok(10u128.pow(decimals as u32))
+}
+
+fn to_lamports(value: u128) -> Result<u64> {
+    u64::try_from(value).map_err(|_| error!(LaunchError::MathOverflow))
+}
+
+/// floor(a * b / d) without overflowing when only the intermediate product is large.
+fn mul_div(a: u128, b: u128, d: u128) -> Result<u128> {
+    require!(d > 0, LaunchError::MathOverflow);
+    if let Some(product) = a.checked_mul(b) {
+        return Ok(product / d);
+    }
+    let (small, large) = if a < b { (a, b) } else { (b, a) };
+    let whole = small.checked_mul(large / d).ok_or(LaunchError::MathOverflow)?;
+    let frac = small.checked_mul(large % d).ok_or(LaunchError::MathOverflow)? / d;
+    whole.checked_add(frac).ok_or(error!(LaunchError::MathOverflow))
+}
+
+/// Largest `q <= cap` with `pred(q)`, for a predicate that holds at 0 and
+/// flips to false at most once. Gallops out from `hint`, then bisects.
+fn search_max(hint: Option<u128>, cap: u128, pred: impl Fn(u128) -> bool) -> u128 {
+    // Invariant: pred(lo) and (hi == cap + 1 or !pred(hi)).
+    let (mut lo, mut hi) = (0u128, cap + 1);
+    if let Some(hint) = hint {
+        let hint = hint.min(cap);
+        let mut step = 1u128;
+        if pred(hint) {
+            lo = hint;
+            while lo + step < hi && pred(lo + step) {
+                lo += step;
+                step *= 2;
+            }
+            hi = hi.min(lo + step);
+        } else {
+            hi = hint;
+            while step <= hi && !pred(hi - step) {
+                hi -= step;
+                step *= 2;
+            }
+            lo = hi.saturating_sub(step);
+        }
+    }
+    while hi - lo > 1 {
+        let mid = lo + (hi - lo) / 2;
+        if pred(mid) {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    lo
+}
+
+/// floor(sqrt(n)) by Newton's method.
+fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    let mut x = 1u128 << (128 - n.leading_zeros()).div_ceil(2);
+    loop {
+        let y = (x + n / x) / 2;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+/// (1 + growth_bps / 10_000) ^ n in `GROWTH_SCALE` fixed point.
+fn growth_power(growth_bps: u16, mut n: u128) -> Result<u128> {
+    let mut base = GROWTH_SCALE + GROWTH_SCALE * growth_bps as u128 / BPS_DENOMINATOR as u128;
+    let mut acc = GROWTH_SCALE;
+    while n > 0 {
+        if n & 1 == 1 {
+            acc = mul_div(acc, base, GROWTH_SCALE)?;
+        }
+        n >>= 1;
+        if n > 0 {
+            base = mul_div(base, base, GROWTH_SCALE)?;
+        }
+    }
+    Ok(acc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{INITIAL_SUPPLY_BASE_UNITS, TOKEN_DECIMALS};
+    use crate::state::curve_kind::{PricePoint, MAX_CURVE_POINTS};
+    use proptest::prelude::*;
+
+    fn piecewise(raw: Vec<(u64, u64)>) -> CurveKind {
+        let mut points = [PricePoint::default(); MAX_CURVE_POINTS];
+        let mut len = 0;
+        let (mut supply, mut price) = (0u64, 0u64);
+        for (i, (dx, dp)) in raw.into_iter().enumerate() {
+            if i > 0 {
+                match supply.checked_add(dx.max(1)) {
+                    Some(next) => supply = next,
+                    None => break,
+                }
+            }
+            price = price.saturating_add(dp.max(1));
+            points[len] = PricePoint { supply, price };
+            len += 1;
+        }
+        CurveKind::PiecewiseLinear { points, len: len as u8 }
+    }
+
+    fn any_curve() -> impl Strategy<Value = CurveKind> {
+        prop_oneof![
+            (1..=u64::MAX, 1..=u64::MAX)
+                .prop_map(|(base_price, slope)| CurveKind::Linear { base_price, slope }),
+            (1..=u64::MAX, 1..=u16::MAX, 1..=u64::MAX).prop_map(|(base_price, growth_bps, step)| {
+                CurveKind::Exponential { base_price, growth_bps, step }
+            }),
+            (1..=u64::MAX, 1..=u64::MAX).prop_map(|(virtual_sol_reserves, virtual_token_reserves)| {
+                CurveKind::ConstantProduct { virtual_sol_reserves, virtual_token_reserves }
+            }),
+            prop::collection::vec((any::<u64>(), any::<u64>()), 2..=MAX_CURVE_POINTS).prop_map(piecewise),
+        ]
+    }
+
+    /// Parameters a creator could actually launch with, so most quotes succeed.
+    fn launchable_curve() -> impl Strategy<Value = CurveKind> {
+        prop_oneof![
+            (1..=1_000_000u64, 1..=10u64)
+                .prop_map(|(base_price, slope)| CurveKind::Linear { base_price, slope }),
+            (1..=1_000u64, 1..=500u16).prop_map(|(base_price, growth_bps)| CurveKind::Exponential {
+                base_price,
+                growth_bps,
+                step: INITIAL_SUPPLY_BASE_UNITS / 100,
+            }),
+            (1_000_000_000..=100_000_000_000u64).prop_map(|virtual_sol_reserves| {
+                CurveKind::ConstantProduct {
+                    virtual_sol_reserves,
+                    virtual_token_reserves: INITIAL_SUPPLY_BASE_UNITS / 10 * 11,
+                }
+            }),
+        ]
+    }
+
+    fn round_trip(curve: &CurveKind, supply: u64, amount: u64, decimals: u8) {
+        let cost = curve.cost_to_buy(supply, amount, decimals);
+        let payout = supply
+            .checked_add(amount)
+            .map(|end| curve.payout_to_sell(end, amount, decimals));
+        if let (Ok(cost), Some(Ok(payout))) = (cost, payout) {
+            assert!(payout <= cost, "payout {payout} > cost {cost} for {curve:?}");
+            assert!(cost - payout <= 1, "rounding gap {cost} - {payout} for {curve:?}");
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn sell_after_buy_never_pays_more(
+            curve in any_curve(),
+            supply in any::<u64>(),
+            amount in any::<u64>(),
+            decimals in 0u8..=18,
+        ) {
+            round_trip(&curve, supply, amount, decimals);
+        }
+
+        #[test]
+        fn launchable_round_trip(
+            curve in launchable_curve(),
+            supply in 0..INITIAL_SUPPLY_BASE_UNITS,
+            amount in 0..INITIAL_SUPPLY_BASE_UNITS,
+        ) {
+            let amount = amount.min(INITIAL_SUPPLY_BASE_UNITS - supply);
+            prop_assert!(curve.cost_to_buy(supply, amount, TOKEN_DECIMALS).is_ok());
+            round_trip(&curve, supply, amount, TOKEN_DECIMALS);
+        }
+
+        #[test]
+        fn split_sells_never_beat_the_buy(
+            curve in any_curve(),
+            supply in any::<u64>(),
+            amount in any::<u64>(),
+            split in any::<u64>(),
+            decimals in 0u8..=18,
+        ) {
+            let first = if amount == 0 { 0 } else { split % amount };
+            let Ok(cost) = curve.cost_to_buy(supply, amount, decimals) else { return Ok(()) };
+            let end = supply + amount;
+            // An overflow on a partial quote rejects the trade, which is safe.
+            let (Ok(a), Ok(b)) = (
+                curve.payout_to_sell(end, first, decimals),
+                curve.payout_to_sell(end - first, amount - first, decimals),
+            ) else { return Ok(()) };
+            prop_assert!(a as u128 + b as u128 <= cost as u128);
+        }
+
+        #[test]
+        fn split_buys_never_cost_less(
+            curve in any_curve(),
+            supply in any::<u64>(),
+            amount in any::<u64>(),
+            split in any::<u64>(),
+            decimals in 0u8..=18,
+        ) {
+            let first = if amount == 0 { 0 } else { split % amount };
+            let Ok(whole) = curve.cost_to_buy(supply, amount, decimals) else { return Ok(()) };
+            let (Ok(a), Ok(b)) = (
+                curve.cost_to_buy(supply, first, decimals),
+                curve.cost_to_buy(supply + first, amount - first, decimals),
+            ) else { return Ok(()) };
+            prop_assert!(a as u128 + b as u128 >= whole as u128);
+        }
+
+        #[test]
+        fn max_buy_is_the_largest_affordable_amount(
+            curve in prop_oneof![any_curve(), launchable_curve()],
+            supply in 0..INITIAL_SUPPLY_BASE_UNITS,
+            lamports in any::<u64>(),
+            cap in any::<u64>(),
+        ) {
+            let Ok(q) = curve.max_buy_for(supply, lamports, cap, TOKEN_DECIMALS) else { return Ok(()) };
+            prop_assert!(q <= cap);
+            prop_assert!(curve.cost_to_buy(supply, q, TOKEN_DECIMALS)? <= lamports);
+            if q < cap {
+                let next = curve.cost_to_buy(supply, q + 1, TOKEN_DECIMALS);
+                prop_assert!(next.map_or(true, |c| c > lamports));
+            }
+        }
+
+        #[test]
+        fn min_sell_is_the_smallest_sufficient_amount(
+            curve in prop_oneof![any_curve(), launchable_curve()],
+            supply in 0..INITIAL_SUPPLY_BASE_UNITS,
+            lamports in 1..=u64::MAX,
+        ) {
+            let Ok(q) = curve.min_sell_for(supply, lamports, TOKEN_DECIMALS) else { return Ok(()) };
+            prop_assert!(q >= 1 && q <= supply);
+            prop_assert!(curve.payout_to_sell(supply, q, TOKEN_DECIMALS)? >= lamports);
+            let less = curve.payout_to_sell(supply, q - 1, TOKEN_DECIMALS);
+            prop_assert!(less.map_or(true, |p| p < lamports));
+        }
+
+        #[test]
+        fn spot_price_never_decreases(
+            curve in any_curve(),
+            supply in any::<u64>(),
+            amount in any::<u64>(),
+            decimals in 0u8..=18,
+        ) {
+            let Some(end) = supply.checked_add(amount) else { return Ok(()) };
+            if let (Ok(before), Ok(after)) =
+                (curve.spot_price(supply, decimals), curve.spot_price(end, decimals))
+            {
+                prop_assert!(after >= before);
+            }
+        }
+    }
+
+    #[test]
+    fn linear_matches_closed_form() {
+        let curve = CurveKind::Linear { base_price: 1_000, slope: 2 };
+        let m = 10u64.pow(TOKEN_DECIMALS as u32);
+        // 1_000 lamports for the first token plus 2 * 10^2 / 2 for the ramp
+        assert_eq!(curve.cost_to_buy(0, 10 * m, TOKEN_DECIMALS).unwrap(), 10_100);
+        assert_eq!(curve.payout_to_sell(10 * m, 10 * m, TOKEN_DECIMALS).unwrap(), 10_100);
+        assert_eq!(curve.spot_price(10 * m, TOKEN_DECIMALS).unwrap(), 1_020);
+    }
+}
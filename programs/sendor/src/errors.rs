@@ -4,7 +4,7 @@ use anchor_lang::prelude::*;
 pub enum LaunchError {
     #[msg("Trading window closed")]
     NotInTradingWindow,
-    #[msg("Daily action already performed")]
+    #[msg("Action limit reached for the trailing 24 hours")]
     ActionAlreadyPerformed,
     #[msg("Sell exceeds 10 % limit")]
     ExceedsSellLimit,
@@ -34,4 +34,38 @@ pub enum LaunchError {
     InvalidParams,
     #[msg("Invalid fee recipient")]
     InvalidFeeRecipient,
+    #[msg("Trading fee exceeds the maximum")]
+    FeeTooHigh,
+    #[msg("Launch is paused")]
+    Paused,
+    #[msg("Emergency exit not available")]
+    EmergencyExitUnavailable,
+    #[msg("No randomness revealed for today")]
+    RandomnessStale,
+    #[msg("Previous randomness round not revealed")]
+    RandomnessPending,
+    #[msg("Invalid randomness reveal")]
+    InvalidRandomness,
+    #[msg("Trading policy outside the allowed bounds")]
+    InvalidPolicy,
+    #[msg("Token extension not supported by this token program")]
+    UnsupportedExtension,
+    #[msg("Transfer fee exceeds the maximum")]
+    InvalidTransferFee,
+    #[msg("Transfer hook accounts missing")]
+    MissingHookAccounts,
+    #[msg("SOL vault does not back the curve's reserve")]
+    ReserveMismatch,
+    #[msg("Payout would leave the account below rent exemption")]
+    BelowRentExemption,
+    #[msg("Invalid vesting allocation")]
+    InvalidVesting,
+    #[msg("Vesting accounts missing")]
+    MissingVestingAccounts,
+    #[msg("Nothing vested to claim")]
+    NothingToClaim,
+    #[msg("Vesting schedule already revoked")]
+    AlreadyRevoked,
+    #[msg("Token metadata needs the metadata pointer extension")]
+    MetadataWithoutPointer,
 }
@@ -0,0 +1,229 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::errors::LaunchError;
+use crate::state::{
+    global_state::GlobalState, launch_metadata::LaunchMetadata, launch_policy::PolicyBounds,
+};
+
+#[event]
+pub struct AdminProposed {
+    pub admin:    Pubkey,
+    pub proposed: Pubkey,
+}
+
+#[event]
+pub struct AdminChanged {
+    pub old_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
+#[event]
+pub struct FeeRecipientUpdated {
+    pub old_recipient: Pubkey,
+    pub new_recipient: Pubkey,
+}
+
+#[event]
+pub struct LaunchFeeUpdated {
+    pub old_fee_lamports: u64,
+    pub new_fee_lamports: u64,
+}
+
+#[event]
+pub struct GlobalPauseSet {
+    pub paused: bool,
+}
+
+#[event]
+pub struct LaunchPauseSet {
+    pub launch_id: u64,
+    pub paused:    bool,
+}
+
+#[event]
+pub struct PolicyBoundsUpdated {
+    pub bounds: PolicyBounds,
+}
+
+#[event]
+pub struct GlobalStateMigrated {
+    pub admin:        Pubkey,
+    pub launch_count: u64,
+}
+
+#[derive(Accounts)]
+pub struct AdminOnly<'info> {
+    #[account(mut, has_one = admin)]
+    pub global_state: Account<'info, GlobalState>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AdminLaunchOnly<'info> {
+    #[account(has_one = admin)]
+    pub global_state: Account<'info, GlobalState>,
+    #[account(mut)]
+    pub launch_metadata: Account<'info, LaunchMetadata>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(
+        mut,
+        constraint = global_state.pending_admin == new_admin.key() @ LaunchError::Unauthorized
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    pub new_admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReallocGlobal<'info> {
+    /// CHECK: an older layout that no longer deserializes; the address is
+    /// pinned by seeds and the owner and discriminator are checked here
+    #[account(mut, owner = crate::ID, seeds = [b"global_v2"], bump)]
+    pub global_state: UncheckedAccount<'info>,
+    /// Must be the admin recorded in the old layout
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Rewrites a global state created under the original layout into the current
+/// one, keeping the admin, fee settings and launch count. The new fields start
+/// as `initialize` sets them and the admin pays the extra rent. Accounts
+/// already at the current size are left alone.
+pub(crate) fn realloc_global(ctx: Context<ReallocGlobal>) -> Result<()> {
+    let info = ctx.accounts.global_state.to_account_info();
+    if info.data_len() >= GlobalState::LEN {
+        return Ok(());
+    }
+
+    let legacy = {
+        let data = info.try_borrow_data()?;
+        require!(data.len() == GlobalState::LEGACY_LEN, LaunchError::InvalidParams);
+        require!(data[..8] == *GlobalState::DISCRIMINATOR, LaunchError::InvalidParams);
+        let word = |at: usize| u64::from_le_bytes(data[at..at + 8].try_into().unwrap());
+        GlobalState {
+            admin: Pubkey::try_from(&data[8..40]).unwrap(),
+            platform_fee_recipient: Pubkey::try_from(&data[40..72]).unwrap(),
+            launch_fee_lamports: word(72),
+            launch_count: word(80),
+            bump: data[88],
+            ..Default::default()
+        }
+    };
+    require_keys_eq!(legacy.admin, ctx.accounts.admin.key(), LaunchError::Unauthorized);
+
+    let rent = Rent::get()?.minimum_balance(GlobalState::LEN);
+    let top_up = rent.saturating_sub(info.lamports());
+    if top_up > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.admin.to_account_info(),
+                    to:   info.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+    info.realloc(GlobalState::LEN, true)?;
+    legacy.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+    emit!(GlobalStateMigrated {
+        admin:        legacy.admin,
+        launch_count: legacy.launch_count,
+    });
+    Ok(())
+}
+
+/// First step of the handover; `Pubkey::default()` cancels a pending proposal.
+pub(crate) fn propose_admin(ctx: Context<AdminOnly>, proposed: Pubkey) -> Result<()> {
+    let global = &mut ctx.accounts.global_state;
+    global.pending_admin = proposed;
+
+    emit!(AdminProposed {
+        admin: global.admin,
+        proposed,
+    });
+    Ok(())
+}
+
+/// Second step: the proposed key signs to prove it is live before taking over.
+pub(crate) fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let global = &mut ctx.accounts.global_state;
+    require_keys_neq!(global.pending_admin, Pubkey::default(), LaunchError::Unauthorized);
+
+    let old_admin = global.admin;
+    global.admin = global.pending_admin;
+    global.pending_admin = Pubkey::default();
+
+    emit!(AdminChanged {
+        old_admin,
+        new_admin: global.admin,
+    });
+    Ok(())
+}
+
+pub(crate) fn set_fee_recipient(ctx: Context<AdminOnly>, new_recipient: Pubkey) -> Result<()> {
+    require_keys_neq!(new_recipient, Pubkey::default(), LaunchError::InvalidFeeRecipient);
+    let global = &mut ctx.accounts.global_state;
+    let old_recipient = global.platform_fee_recipient;
+    global.platform_fee_recipient = new_recipient;
+
+    emit!(FeeRecipientUpdated {
+        old_recipient,
+        new_recipient,
+    });
+    Ok(())
+}
+
+pub(crate) fn set_launch_fee(ctx: Context<AdminOnly>, new_fee_lamports: u64) -> Result<()> {
+    let global = &mut ctx.accounts.global_state;
+    let old_fee_lamports = global.launch_fee_lamports;
+    global.launch_fee_lamports = new_fee_lamports;
+
+    emit!(LaunchFeeUpdated {
+        old_fee_lamports,
+        new_fee_lamports,
+    });
+    Ok(())
+}
+
+/// Halts buy, sell, transfer and create_launch on every launch.
+/// See `emergency_sell` for the holder exit path.
+pub(crate) fn set_global_pause(ctx: Context<AdminOnly>, paused: bool) -> Result<()> {
+    let global = &mut ctx.accounts.global_state;
+    if paused && !global.paused {
+        global.paused_at = Clock::get()?.unix_timestamp;
+    } else if !paused {
+        global.paused_at = 0;
+    }
+    global.paused = paused;
+
+    emit!(GlobalPauseSet { paused });
+    Ok(())
+}
+
+pub(crate) fn set_launch_pause(ctx: Context<AdminLaunchOnly>, paused: bool) -> Result<()> {
+    let launch = &mut ctx.accounts.launch_metadata;
+    launch.set_pause(Some(paused), None, Clock::get()?.unix_timestamp);
+
+    emit!(LaunchPauseSet {
+        launch_id: launch.launch_id,
+        paused,
+    });
+    Ok(())
+}
+
+/// Limits what creators may choose at `create_launch`; existing launches keep
+/// their policy.
+pub(crate) fn set_policy_bounds(ctx: Context<AdminOnly>, bounds: PolicyBounds) -> Result<()> {
+    bounds.validate()?;
+    ctx.accounts.global_state.policy_bounds = bounds;
+
+    emit!(PolicyBoundsUpdated { bounds });
+    Ok(())
+}
@@ -0,0 +1,134 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use crate::constants::TRANSFER_HOOK_PROGRAM_ID;
+use crate::errors::LaunchError;
+use crate::state::{
+    launch_metadata::LaunchMetadata,
+    launch_policy::{Allowance, LaunchPolicy},
+    user_record::{RollingLimit, UserRecord},
+    window_schedule::{day_of, DAY},
+};
+use crate::transfer_hook;
+
+#[event]
+pub struct UserRecordMigrated {
+    pub user:            Pubkey,
+    pub launch_metadata: Pubkey,
+}
+
+#[derive(Accounts)]
+pub struct GetAllowance<'info> {
+    #[account(has_one = token_mint)]
+    pub launch_metadata: Account<'info, LaunchMetadata>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    #[account(has_one = launch_metadata)]
+    pub launch_policy: Account<'info, LaunchPolicy>,
+    /// CHECK: only used to derive the record and match the token account
+    pub user: UncheckedAccount<'info>,
+    #[account(
+        constraint = user_token_account.owner == user.key(),
+        constraint = user_token_account.mint  == launch_metadata.token_mint
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// Absent until the holder first sells or transfers
+    #[account(seeds = [b"user", launch_metadata.key().as_ref(), user.key().as_ref()], bump)]
+    pub user_record: Option<Account<'info, UserRecord>>,
+    /// CHECK: the holder's record in the hook, which counts their transfers
+    /// when the mint has the transfer hook; read only
+    #[account(
+        seeds = [b"record", token_mint.key().as_ref(), user.key().as_ref()],
+        seeds::program = TRANSFER_HOOK_PROGRAM_ID,
+        bump
+    )]
+    pub transfer_record: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateUserRecord<'info> {
+    pub launch_metadata: Account<'info, LaunchMetadata>,
+    /// CHECK: an older layout that no longer deserializes; the address is
+    /// pinned by seeds and the owner and discriminator are checked here
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [b"user", launch_metadata.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub user_record: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Remaining sell and transfer allowance for a holder, returned to simulating
+/// clients rather than stored.
+pub(crate) fn get_allowance(ctx: Context<GetAllowance>) -> Result<Allowance> {
+    let now = Clock::get()?.unix_timestamp;
+    let (sell, transfer) = ctx
+        .accounts
+        .user_record
+        .as_ref()
+        .map_or((RollingLimit::default(), RollingLimit::default()), |r| (r.sell, r.transfer));
+    let transfer = transfer_hook::transfer_usage(
+        &ctx.accounts.token_mint.to_account_info(),
+        ctx.accounts.transfer_record.as_ref().map(|r| r.as_ref()),
+        transfer,
+    )?;
+    ctx.accounts
+        .launch_policy
+        .allowance(&sell, &transfer, now, ctx.accounts.user_token_account.amount)
+}
+
+/// Grows a record written before sells and transfers were tracked apart. The
+/// user pays the extra rent. A legacy record that acted today keeps both
+/// allowances spent until the next UTC midnight, matching the old rule.
+pub(crate) fn migrate_user_record(ctx: Context<MigrateUserRecord>) -> Result<()> {
+    let info = ctx.accounts.user_record.to_account_info();
+    if info.data_len() >= UserRecord::LEN {
+        return Ok(());
+    }
+
+    let legacy_day = {
+        let data = info.try_borrow_data()?;
+        require!(data.len() >= 8 + 32, LaunchError::InvalidParams);
+        require!(data[..8] == *UserRecord::DISCRIMINATOR, LaunchError::InvalidParams);
+        (data.len() == UserRecord::LEGACY_LEN)
+            .then(|| u64::from_le_bytes(data[40..48].try_into().unwrap()))
+    };
+
+    let rent = Rent::get()?.minimum_balance(UserRecord::LEN);
+    let top_up = rent.saturating_sub(info.lamports());
+    if top_up > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.user.to_account_info(),
+                    to:   info.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+    info.realloc(UserRecord::LEN, true)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    // A legacy action today counts from the start of its day, as it did.
+    let mut spent = RollingLimit::default();
+    if let Some(day) = legacy_day.filter(|day| *day == day_of(now)) {
+        spent.record(day as i64 * DAY, 0, 0)?;
+    }
+    let record = UserRecord {
+        user: ctx.accounts.user.key(),
+        sell: spent,
+        transfer: spent,
+    };
+    record.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+    emit!(UserRecordMigrated {
+        user:            record.user,
+        launch_metadata: ctx.accounts.launch_metadata.key(),
+    });
+    Ok(())
+}
@@ -1,36 +1,57 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
 use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::{self, Token, TokenAccount, TransferChecked};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
 
-use crate::constants::*;
 use crate::errors::LaunchError;
-use crate::state::{bonding_curve_state::BondingCurveState, launch_metadata::LaunchMetadata};
-
-const DAY: i64 = 86_400;
-const WIN: i64 = 900;
-const MIN_GAP: i64 = 43_200;
-const MAX_GAP: i64 = 64_800;
+use crate::token_extensions;
+use crate::transfer_hook;
+use crate::state::{
+    bonding_curve_state::BondingCurveState,
+    global_state::GlobalState,
+    launch_metadata::LaunchMetadata,
+    launch_policy::LaunchPolicy,
+    randomness_oracle::RandomnessOracle,
+    trade_fees::{FeeSplit, TradeFees},
+};
 
 #[event]
 pub struct PurchaseEvent {
     pub buyer: Pubkey,
     pub qty:   u64,
     pub cost:  u64,
+    pub protocol_fee: u64,
+    pub creator_fee:  u64,
 }
 
 #[derive(Accounts)]
 pub struct Buy<'info> {
+    #[account(seeds = [b"global_v2"], bump = global_state.bump)]
+    pub global_state: Account<'info, GlobalState>,
+
     #[account(mut, has_one = token_mint, has_one = vault)]
     pub launch_metadata: Account<'info, LaunchMetadata>,
 
     #[account(mut, has_one = launch_metadata)]
     pub bonding_curve: Account<'info, BondingCurveState>,
 
+    #[account(mut, has_one = launch_metadata)]
+    pub launch_policy: Account<'info, LaunchPolicy>,
+
+    #[account(
+        mut,
+        seeds = [b"sol_vault", launch_metadata.launch_id.to_le_bytes().as_ref()],
+        bump = launch_metadata.sol_vault_bump
+    )]
+    pub sol_vault: SystemAccount<'info>,
+
+    #[account(seeds = [b"randomness"], bump = randomness.bump)]
+    pub randomness: Account<'info, RandomnessOracle>,
+
+    #[account(mut, mint::token_program = token_program)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
     #[account(mut)]
-    pub token_mint: Account<'info, anchor_spl::token::Mint>,
-    #[account(mut)]
-    pub vault:      Account<'info, TokenAccount>,
+    pub vault:      InterfaceAccount<'info, TokenAccount>,
 
     #[account(mut)]
     pub buyer: Signer<'info>,
@@ -39,118 +60,175 @@ pub struct Buy<'info> {
         init_if_needed,
         payer = buyer,
         associated_token::mint      = token_mint,
-        associated_token::authority = buyer
+        associated_token::authority = buyer,
+        associated_token::token_program = token_program
     )]
-    pub buyer_token_account: Account<'info, TokenAccount>,
+    pub buyer_token_account: InterfaceAccount<'info, TokenAccount>,
 
-    pub token_program:            Program<'info, Token>,
+    #[account(mut, address = global_state.platform_fee_recipient @ LaunchError::InvalidFeeRecipient)]
+    pub platform_fee_recipient: SystemAccount<'info>,
+
+    pub token_program:            Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program:           Program<'info, System>,
     pub rent:                     Sysvar<'info, Rent>,
 }
 
-pub fn buy(ctx: Context<Buy>, amount: u64, max_cost: u64) -> Result<()> {
-    let launch  = &mut ctx.accounts.launch_metadata;
-    let curve   = &mut ctx.accounts.bonding_curve;
-    let vault   = &mut ctx.accounts.vault;
-    let buyer   = &ctx.accounts.buyer;
-    let buyer_token_account = &ctx.accounts.buyer_token_account;
-
-    let now = Clock::get()?.unix_timestamp;
-    let today = (now / DAY) as u64;
-
-    if today > launch.current_day {
-        launch.current_day = today;
-        let seed = Clock::get()?.slot.wrapping_add(launch.launch_id);
-        let w1   = (seed % ((DAY - MAX_GAP - WIN) as u64)) as i64;
-        let gap  = MIN_GAP + ((seed >> 8) % ((MAX_GAP - MIN_GAP) as u64)) as i64;
-        let w2   = w1 + gap;
-        require!(w2 + WIN <= DAY, LaunchError::InvalidWindowTimes);
-        launch.window1_start = w1;
-        launch.window2_start = w2;
-    }
-
-    require!(curve.decimals <= 18, LaunchError::InvalidDecimals);
+pub(crate) fn buy<'info>(ctx: Context<'_, '_, '_, 'info, Buy<'info>>, amount: u64, max_cost: u64) -> Result<()> {
+    let accounts = ctx.accounts;
+    accounts.roll_trading_day()?;
 
-    let available = INITIAL_SUPPLY_BASE_UNITS
-        .checked_sub(curve.current_supply)
-        .ok_or(LaunchError::InsufficientSupply)?;
+    let available = accounts.available_supply()?;
     require!(amount <= available, LaunchError::InsufficientSupply);
 
-    let cost = compute_cost(curve, amount)?;
-    require!(cost <= max_cost, LaunchError::SlippageExceeded);
-
-    let buyer_lamports = **buyer.to_account_info().lamports.borrow();
-    require!(cost <= buyer_lamports, LaunchError::InsufficientFunds);
-
-    system_program::transfer(
-        CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: buyer.to_account_info(),
-                to:   launch.to_account_info(),
-            },
-        ),
-        cost,
-    )?;
-
-    let id_bytes = launch.launch_id.to_le_bytes();
-    let seeds: &[&[u8]] = &[b"launch", id_bytes.as_ref(), &[launch.bump]];
-
-    token::transfer_checked(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            TransferChecked {
-                from:      vault.to_account_info(),
-                to:        buyer_token_account.to_account_info(),
-                authority: launch.to_account_info(),
-                mint:      ctx.accounts.token_mint.to_account_info(),
-            },
-            &[seeds],
-        ),
-        amount,
-        curve.decimals,
-    )?;
-
-    curve.current_supply = curve
-        .current_supply
-        .checked_add(amount)
-        .ok_or(LaunchError::MathOverflow)?;
-
-    emit!(PurchaseEvent {
-        buyer: buyer.key(),
-        qty:   amount,
-        cost,
-    });
-
-    Ok(())
+    let cost = accounts.bonding_curve.cost_to_buy(amount)?;
+    let fees = accounts.fees().charge(cost);
+    let total = cost.checked_add(fees.total()).ok_or(LaunchError::MathOverflow)?;
+    require!(total <= max_cost, LaunchError::SlippageExceeded);
+
+    accounts.settle(amount, cost, fees, ctx.remaining_accounts)
+}
+
+/// Spends at most `lamports_in` (fees included) on as many tokens as the curve
+/// allows and returns the unspent dust, which never leaves the buyer's wallet.
+/// `min_tokens_out` is checked against what arrives after any transfer fee.
+pub(crate) fn buy_exact_in<'info>(
+    ctx: Context<'_, '_, '_, 'info, Buy<'info>>,
+    lamports_in: u64,
+    min_tokens_out: u64,
+) -> Result<u64> {
+    let accounts = ctx.accounts;
+    accounts.roll_trading_day()?;
+
+    let available = accounts.available_supply()?;
+    let budget = accounts.fees().max_principal(lamports_in);
+    let amount = accounts.bonding_curve.max_buy_for(budget, available)?;
+    require!(amount > 0, LaunchError::InsufficientFunds);
+    let fee = token_extensions::transfer_fee(&accounts.token_mint.to_account_info(), amount)?;
+    require!(amount - fee >= min_tokens_out, LaunchError::SlippageExceeded);
+
+    let cost = accounts.bonding_curve.cost_to_buy(amount)?;
+    let fees = accounts.fees().charge(cost);
+    accounts.settle(amount, cost, fees, ctx.remaining_accounts)?;
+
+    Ok(lamports_in - cost - fees.total())
 }
 
-fn compute_cost(curve: &BondingCurveState, qty: u64) -> Result<u64> {
-    let m       = 10u128.pow(curve.decimals as u32);
-    let base    = curve.base_price     as u128;
-    let slope   = curve.slope          as u128;
-    let supply  = curve.current_supply as u128;
-    let qty128  = qty                  as u128;
-
-    let part1 = base
-        .checked_mul(qty128)
-        .ok_or(LaunchError::MathOverflow)?
-        .checked_mul(m)
-        .ok_or(LaunchError::MathOverflow)?;
-
-    let part2 = slope
-        .checked_mul(
-            supply
-                .checked_mul(qty128)
-                .ok_or(LaunchError::MathOverflow)?
-                .checked_add(qty128 * (qty128 + 1) / 2)
-                .ok_or(LaunchError::MathOverflow)?,
-        )
-        .ok_or(LaunchError::MathOverflow)?;
-
-    let numerator = part1.checked_add(part2).ok_or(LaunchError::MathOverflow)?;
-    let denom     = m.checked_mul(m).ok_or(LaunchError::MathOverflow)?;
-
-    Ok((numerator / denom) as u64)
+impl<'info> Buy<'info> {
+    fn roll_trading_day(&mut self) -> Result<()> {
+        require!(!self.launch_metadata.is_paused(&self.global_state), LaunchError::Paused);
+        let now = Clock::get()?.unix_timestamp;
+        self.launch_policy.roll_windows(&self.randomness, now)
+    }
+
+    fn fees(&self) -> TradeFees {
+        self.launch_metadata.trade_fees(&self.global_state.trade_fees)
+    }
+
+    fn available_supply(&self) -> Result<u64> {
+        require!(self.bonding_curve.decimals <= 18, LaunchError::InvalidDecimals);
+        self.bonding_curve.available_supply()
+    }
+
+    /// Moves `cost` plus fees in and `amount` tokens out, then records the sale.
+    /// Any transfer fee comes out of what the buyer receives, so the curve's
+    /// supply still tracks what left the vault. The cost joins the reserve in
+    /// the SOL vault and the creator's cut is parked on the launch account.
+    /// `hook_accounts` are forwarded to the mint's transfer hook.
+    fn settle(
+        &mut self,
+        amount: u64,
+        cost: u64,
+        fees: FeeSplit,
+        hook_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let launch = &mut self.launch_metadata;
+        let curve  = &mut self.bonding_curve;
+        let buyer  = &self.buyer;
+
+        let total = cost.checked_add(fees.total()).ok_or(LaunchError::MathOverflow)?;
+        let buyer_lamports = **buyer.to_account_info().lamports.borrow();
+        require!(total <= buyer_lamports, LaunchError::InsufficientFunds);
+
+        system_program::transfer(
+            CpiContext::new(
+                self.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: buyer.to_account_info(),
+                    to:   self.sol_vault.to_account_info(),
+                },
+            ),
+            cost,
+        )?;
+
+        if fees.creator > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    self.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: buyer.to_account_info(),
+                        to:   launch.to_account_info(),
+                    },
+                ),
+                fees.creator,
+            )?;
+        }
+
+        if fees.protocol > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    self.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: buyer.to_account_info(),
+                        to:   self.platform_fee_recipient.to_account_info(),
+                    },
+                ),
+                fees.protocol,
+            )?;
+        }
+
+        launch.creator_fees_accrued = launch
+            .creator_fees_accrued
+            .checked_add(fees.creator)
+            .ok_or(LaunchError::MathOverflow)?;
+
+        let id_bytes = launch.launch_id.to_le_bytes();
+        let seeds: &[&[u8]] = &[b"launch", id_bytes.as_ref(), &[launch.bump]];
+
+        transfer_hook::transfer_checked(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from:      self.vault.to_account_info(),
+                    to:        self.buyer_token_account.to_account_info(),
+                    authority: launch.to_account_info(),
+                    mint:      self.token_mint.to_account_info(),
+                },
+                &[seeds],
+            )
+            .with_remaining_accounts(hook_accounts.to_vec()),
+            amount,
+            curve.decimals,
+        )?;
+
+        curve.current_supply = curve
+            .current_supply
+            .checked_add(amount)
+            .ok_or(LaunchError::MathOverflow)?;
+        curve.real_sol_reserves = curve
+            .real_sol_reserves
+            .checked_add(cost)
+            .ok_or(LaunchError::MathOverflow)?;
+        curve.check_reserves(&self.sol_vault.to_account_info())?;
+
+        emit!(PurchaseEvent {
+            buyer: buyer.key(),
+            qty:   amount,
+            cost,
+            protocol_fee: fees.protocol,
+            creator_fee:  fees.creator,
+        });
+
+        Ok(())
+    }
 }
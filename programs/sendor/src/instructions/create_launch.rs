@@ -1,7 +1,6 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, MintTo, SetAuthority};
-use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::spl_token::instruction::AuthorityType;
+use anchor_spl::associated_token::{self, AssociatedToken};
+use anchor_spl::token_interface::{self, MintTo, TokenInterface};
 use anchor_lang::system_program; // Required for SystemProgram.transfer
 
 use crate::constants::*;
@@ -9,11 +8,18 @@ use crate::errors::LaunchError;
 use crate::state::{
     global_state::GlobalState,
     launch_metadata::LaunchMetadata,
-    bonding_curve_state::BondingCurveState,
+    launch_policy::{LaunchPolicy, PolicyParams},
+    bonding_curve_state::{BondingCurveState, SupplyParams},
+    curve_kind::CurveKind,
+    graduation::GraduationThreshold,
+    vesting::VestingParams,
+    window_schedule::WindowSchedule,
 };
+use crate::instructions::vesting::{self, OpenVesting};
+use crate::curve::PricingCurve;
+use crate::token_extensions::{self, CreateMint, MintMetadata, TokenExtensions};
+use crate::transfer_hook::{self, InitializeHook, EXTRA_ACCOUNT_METAS_SEED};
 
-const MIN_BASE_PRICE_LAMPORTS: u64 = 1;
-// const MAX_FINAL_PRICE_LAMPORTS: u64 = 100_000_000_000; // 100 SOL in lamports, adjust as needed
 const MAX_FINAL_PRICE_LAMPORTS: u64 = 100 * 1_000_000_000; // Example: 100 SOL
 
 
@@ -23,13 +29,29 @@ pub struct LaunchCreated {
     pub creator: Pubkey,
     pub mint: Pubkey,
     pub vault: Pubkey,
+    pub curve: CurveKind,
+    pub supply: SupplyParams,
+    pub graduation: GraduationThreshold,
+    pub policy: PolicyParams,
+    pub token_program: Pubkey,
+    pub extensions: TokenExtensions,
     pub token_name: String,
     pub token_symbol: String,
     pub token_uri: String,
 }
 
 #[derive(Accounts)]
-#[instruction(token_name: String, token_symbol: String, token_uri: String)]
+#[instruction(
+    curve: CurveKind,
+    supply: SupplyParams,
+    graduation: GraduationThreshold,
+    allocations: Vec<VestingParams>,
+    policy: PolicyParams,
+    extensions: TokenExtensions,
+    token_name: String,
+    token_symbol: String,
+    token_uri: String
+)]
 pub struct CreateLaunch<'info> {
     #[account(mut)]
     pub global_state: Account<'info, GlobalState>,
@@ -55,19 +77,42 @@ pub struct CreateLaunch<'info> {
     #[account(
         init,
         payer = creator,
-        mint::decimals = TOKEN_DECIMALS,
-        mint::authority = launch_metadata.key(),
-        mint::freeze_authority = launch_metadata.key(),
+        space = LaunchPolicy::LEN,
+        seeds = [b"policy", global_state.launch_count.to_le_bytes().as_ref()],
+        bump
     )]
-    pub token_mint: Account<'info, Mint>,
+    pub launch_policy: Account<'info, LaunchPolicy>,
 
+    /// System-owned PDA holding the curve's SOL; funded to rent exemption
+    /// here so buys of any size can land in it
     #[account(
-        init,
-        payer = creator,
-        associated_token::mint = token_mint,
-        associated_token::authority = launch_metadata
+        mut,
+        seeds = [b"sol_vault", global_state.launch_count.to_le_bytes().as_ref()],
+        bump
     )]
-    pub vault: Account<'info, TokenAccount>,
+    pub sol_vault: SystemAccount<'info>,
+
+    /// Created with the chosen extensions during this instruction
+    #[account(mut)]
+    pub token_mint: Signer<'info>,
+
+    /// CHECK: created as the launch's associated token account during this
+    /// instruction; the associated token program checks the address
+    #[account(mut)]
+    pub vault: UncheckedAccount<'info>,
+
+    /// CHECK: created by the hook program; only needed with the transfer hook
+    #[account(
+        mut,
+        seeds = [EXTRA_ACCOUNT_METAS_SEED, token_mint.key().as_ref()],
+        seeds::program = TRANSFER_HOOK_PROGRAM_ID,
+        bump
+    )]
+    pub extra_account_meta_list: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: pinned to the known hook program
+    #[account(executable, address = TRANSFER_HOOK_PROGRAM_ID)]
+    pub transfer_hook_program: Option<UncheckedAccount<'info>>,
 
     #[account(mut)]
     pub creator: Signer<'info>,
@@ -76,44 +121,77 @@ pub struct CreateLaunch<'info> {
     pub platform_fee_recipient: SystemAccount<'info>,
 
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub rent: Sysvar<'info, Rent>,
 }
 
-pub fn create_launch(
-    ctx: Context<CreateLaunch>,
-    base_price: u64,
-    slope: u64,
+/// Opens a launch. Each entry in `allocations` is held back from the curve in
+/// its own vesting schedule; remaining accounts carry one
+/// `[schedule, schedule token account]` pair per allocation, in order.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn create_launch<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreateLaunch<'info>>,
+    curve_kind: CurveKind,
+    supply: SupplyParams,
+    graduation: GraduationThreshold,
+    allocations: Vec<VestingParams>,
+    policy_params: PolicyParams,
+    extensions: TokenExtensions,
     token_name: String,
     token_symbol: String,
     token_uri: String,
 ) -> Result<()> {
     // Parameter Validations
-    require!(TOKEN_DECIMALS <= 18, LaunchError::InvalidDecimals);
-    require!(base_price >= MIN_BASE_PRICE_LAMPORTS, LaunchError::InvalidParams);
-    require!(slope > 0, LaunchError::InvalidParams);
-    require!(!token_name.is_empty() && token_name.len() <= 32, LaunchError::InvalidParams);
-    require!(!token_symbol.is_empty() && token_symbol.len() <= 10, LaunchError::InvalidParams);
-    require!(!token_uri.is_empty() && token_uri.len() <= 200, LaunchError::InvalidParams);
+    let sale_supply = supply.sale_supply()?;
+    curve_kind.validate(sale_supply, supply.decimals, MAX_FINAL_PRICE_LAMPORTS)?;
+    let vested_supply = VestingParams::total(&allocations, supply.total_supply)?;
+    require!(
+        vested_supply <= supply.total_supply - sale_supply,
+        LaunchError::InvalidVesting
+    );
+    require!(
+        ctx.remaining_accounts.len() == 2 * allocations.len(),
+        LaunchError::MissingVestingAccounts
+    );
+    // A raise too large for a u64 could not be held as a reserve anyway.
+    let max_raise = curve_kind
+        .cost_to_buy(0, sale_supply, supply.decimals)
+        .unwrap_or(u64::MAX);
+    graduation.validate(sale_supply, max_raise)?;
+    // Metadata is only published through the mint's own metadata pointer;
+    // anything given without it would be stored where no wallet looks.
+    if extensions.metadata_pointer {
+        require!(
+            !token_name.is_empty() && token_name.len() <= MAX_TOKEN_NAME_LEN,
+            LaunchError::InvalidParams
+        );
+        require!(
+            !token_symbol.is_empty() && token_symbol.len() <= MAX_TOKEN_SYMBOL_LEN,
+            LaunchError::InvalidParams
+        );
+        require!(
+            !token_uri.is_empty() && token_uri.len() <= MAX_TOKEN_URI_LEN,
+            LaunchError::InvalidParams
+        );
+    } else {
+        require!(
+            token_name.is_empty() && token_symbol.is_empty() && token_uri.is_empty(),
+            LaunchError::MetadataWithoutPointer
+        );
+    }
+    extensions.validate(ctx.accounts.token_program.key)?;
 
     let global = &mut ctx.accounts.global_state;
+    require!(!global.paused, LaunchError::Paused);
+    global.policy_bounds.check(&policy_params)?;
+    let schedule = WindowSchedule::spread(policy_params.windows_per_day, policy_params.window_duration)?;
     let launch = &mut ctx.accounts.launch_metadata;
     let curve = &mut ctx.accounts.bonding_curve;
     let mint_account = &ctx.accounts.token_mint;
     let vault_account = &ctx.accounts.vault;
     let creator_account = &ctx.accounts.creator;
 
-    // Check max price
-    let max_price = base_price
-        .checked_add(
-            slope
-                .checked_mul(INITIAL_SUPPLY_BASE_UNITS)
-                .ok_or(LaunchError::MathOverflow)?,
-        )
-        .ok_or(LaunchError::MathOverflow)?;
-    require!(max_price <= MAX_FINAL_PRICE_LAMPORTS, LaunchError::InvalidParams);
-
     // 1. Platform Fee Payment
     if global.launch_fee_lamports > 0 {
         require_keys_eq!(ctx.accounts.platform_fee_recipient.key(), global.platform_fee_recipient, LaunchError::InvalidFeeRecipient);
@@ -131,30 +209,103 @@ pub fn create_launch(
     let launch_id_bytes = global.launch_count.to_le_bytes();
     let (_pda_key, launch_bump) = Pubkey::find_program_address(&[b"launch", &launch_id_bytes], ctx.program_id);
 
+    launch.creator = creator_account.key();
     launch.token_mint = mint_account.key();
     launch.vault = vault_account.key();
     launch.launch_id = global.launch_count;
-    launch.current_day = 0;
-    launch.window1_start = 0;
-    launch.window2_start = 0;
     launch.bump = launch_bump;
+    launch.sol_vault_bump = ctx.bumps.sol_vault;
+    launch.fee_override = None;
+    launch.creator_fees_accrued = 0;
+    launch.paused = false;
+    launch.creator_paused = false;
+    launch.paused_at = 0;
     // Store token metadata
     launch.token_name = token_name.clone();
     launch.token_symbol = token_symbol.clone();
     launch.token_uri = token_uri.clone();
 
     curve.launch_metadata = launch.key();
-    curve.base_price = base_price;
-    curve.slope = slope;
+    curve.curve = curve_kind;
     curve.current_supply = 0;
-    curve.decimals = TOKEN_DECIMALS;
+    curve.decimals = supply.decimals;
+    curve.total_supply = supply.total_supply;
+    curve.sale_supply = sale_supply;
+    curve.real_sol_reserves = 0;
+    curve.vested_supply = vested_supply;
+    curve.graduation = graduation;
 
-    // 3. Mint Initial Supply to Vault
+    let policy = &mut ctx.accounts.launch_policy;
+    policy.launch_metadata = launch.key();
+    policy.launch_id = global.launch_count;
+    policy.sell_limit_percent = policy_params.sell_limit_percent;
+    policy.transfer_limit_percent = policy_params.transfer_limit_percent;
+    policy.actions_per_day = policy_params.actions_per_day;
+    policy.schedule = schedule;
+    policy.bump = ctx.bumps.launch_policy;
+
+    let vault_rent = Rent::get()?.minimum_balance(0);
+    let vault_top_up = vault_rent.saturating_sub(ctx.accounts.sol_vault.lamports());
+    if vault_top_up > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: creator_account.to_account_info(),
+                    to: ctx.accounts.sol_vault.to_account_info(),
+                },
+            ),
+            vault_top_up,
+        )?;
+    }
+
+    // 3. Create the mint and vault, then mint everything not vesting to the vault
     let launch_seeds: &[&[u8]] = &[b"launch", launch_id_bytes.as_ref(), &[launch.bump]];
+    let token_program = ctx.accounts.token_program.to_account_info();
+
+    token_extensions::create_mint(
+        CreateMint {
+            payer: creator_account.to_account_info(),
+            mint: mint_account.to_account_info(),
+            authority: launch.to_account_info(),
+            token_program: token_program.clone(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        },
+        &extensions,
+        supply.decimals,
+    )?;
+
+    if extensions.metadata_pointer {
+        token_extensions::init_token_metadata(
+            MintMetadata {
+                payer: creator_account.to_account_info(),
+                mint: mint_account.to_account_info(),
+                authority: launch.to_account_info(),
+                token_program: token_program.clone(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+            },
+            token_name.clone(),
+            token_symbol.clone(),
+            token_uri.clone(),
+            &[launch_seeds],
+        )?;
+    }
+
+    associated_token::create(CpiContext::new(
+        ctx.accounts.associated_token_program.to_account_info(),
+        associated_token::Create {
+            payer: creator_account.to_account_info(),
+            associated_token: vault_account.to_account_info(),
+            authority: launch.to_account_info(),
+            mint: mint_account.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            token_program: token_program.clone(),
+        },
+    ))?;
 
-    token::mint_to(
+    token_interface::mint_to(
         CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
+            token_program,
             MintTo {
                 mint: mint_account.to_account_info(),
                 to: vault_account.to_account_info(),
@@ -162,19 +313,73 @@ pub fn create_launch(
             },
             &[launch_seeds],
         ),
-        INITIAL_SUPPLY_BASE_UNITS,
+        supply.total_supply - vested_supply,
     )?;
 
+    // 4. Hold each allocation in its vesting schedule
+    let open = OpenVesting {
+        payer: creator_account.to_account_info(),
+        launch_metadata: launch.to_account_info(),
+        mint: mint_account.to_account_info(),
+        token_program: ctx.accounts.token_program.to_account_info(),
+        associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+    };
+    let start = Clock::get()?.unix_timestamp;
+    for (params, pair) in allocations.iter().zip(ctx.remaining_accounts.chunks(2)) {
+        vesting::open_vesting(
+            &open,
+            &pair[0],
+            &pair[1],
+            params,
+            start,
+            creator_account.key(),
+            launch_seeds,
+        )?;
+    }
+
+    // 5. Register the hook's extra accounts so direct transfers are limited too
+    if extensions.transfer_hook {
+        let (Some(hook_program), Some(meta_list)) = (
+            &ctx.accounts.transfer_hook_program,
+            &ctx.accounts.extra_account_meta_list,
+        ) else {
+            return err!(LaunchError::MissingHookAccounts);
+        };
+        transfer_hook::initialize_extra_account_meta_list(
+            hook_program.to_account_info(),
+            InitializeHook {
+                payer: creator_account.to_account_info(),
+                extra_account_meta_list: meta_list.to_account_info(),
+                mint: mint_account.to_account_info(),
+                launch_metadata: launch.to_account_info(),
+                launch_policy: policy.to_account_info(),
+                global_state: global.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+            },
+            &[launch_seeds],
+        )?;
+    }
+
     // Emit event
     emit!(LaunchCreated {
         id: launch.launch_id,
         creator: creator_account.key(),
         mint: mint_account.key(),
         vault: vault_account.key(),
+        curve: curve_kind,
+        supply,
+        graduation,
+        policy: policy_params,
+        token_program: ctx.accounts.token_program.key(),
+        extensions,
         token_name,
         token_symbol,
         token_uri,
     });
 
+    // The next launch gets fresh PDAs
+    global.launch_count = global.launch_count.checked_add(1).ok_or(LaunchError::MathOverflow)?;
+
     Ok(())
 }
@@ -0,0 +1,138 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenInterface;
+use crate::constants::MAX_TOKEN_URI_LEN;
+use crate::errors::LaunchError;
+use crate::state::launch_metadata::LaunchMetadata;
+use crate::token_extensions::{self, MintMetadata};
+
+#[event]
+pub struct TokenUriUpdated {
+    pub launch_id: u64,
+    pub token_uri: String,
+}
+
+#[event]
+pub struct CreatorPauseSet {
+    pub launch_id: u64,
+    pub paused:    bool,
+}
+
+#[event]
+pub struct CreatorFeesClaimed {
+    pub launch_id: u64,
+    pub creator:   Pubkey,
+    pub amount:    u64,
+}
+
+#[event]
+pub struct CreatorTransferred {
+    pub launch_id:   u64,
+    pub old_creator: Pubkey,
+    pub new_creator: Pubkey,
+}
+
+#[derive(Accounts)]
+pub struct CreatorOnly<'info> {
+    #[account(mut, has_one = creator)]
+    pub launch_metadata: Account<'info, LaunchMetadata>,
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateTokenUri<'info> {
+    #[account(mut, has_one = creator, has_one = token_mint)]
+    pub launch_metadata: Account<'info, LaunchMetadata>,
+    /// CHECK: the launch's mint; only written through the token program
+    #[account(mut, owner = token_program.key())]
+    pub token_mint: UncheckedAccount<'info>,
+    /// Pays for the mint's metadata growing with a longer URI
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimCreatorFees<'info> {
+    #[account(mut, has_one = creator)]
+    pub launch_metadata: Account<'info, LaunchMetadata>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+}
+
+/// Points the token's metadata, kept in the mint and mirrored on the launch,
+/// at a new off-chain URI. The launch account closes at migration, which
+/// freezes the URI from then on.
+pub(crate) fn update_token_uri(ctx: Context<UpdateTokenUri>, token_uri: String) -> Result<()> {
+    require!(
+        !token_uri.is_empty() && token_uri.len() <= MAX_TOKEN_URI_LEN,
+        LaunchError::InvalidParams
+    );
+    let launch = &mut ctx.accounts.launch_metadata;
+    launch.token_uri = token_uri.clone();
+
+    let id_bytes = launch.launch_id.to_le_bytes();
+    let seeds: &[&[u8]] = &[b"launch", id_bytes.as_ref(), &[launch.bump]];
+    token_extensions::update_token_uri(
+        MintMetadata {
+            payer: ctx.accounts.creator.to_account_info(),
+            mint: ctx.accounts.token_mint.to_account_info(),
+            authority: launch.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        },
+        token_uri.clone(),
+        &[seeds],
+    )?;
+
+    emit!(TokenUriUpdated {
+        launch_id: launch.launch_id,
+        token_uri,
+    });
+    Ok(())
+}
+
+pub(crate) fn set_creator_pause(ctx: Context<CreatorOnly>, paused: bool) -> Result<()> {
+    let launch = &mut ctx.accounts.launch_metadata;
+    launch.set_pause(None, Some(paused), Clock::get()?.unix_timestamp);
+
+    emit!(CreatorPauseSet {
+        launch_id: launch.launch_id,
+        paused,
+    });
+    Ok(())
+}
+
+pub(crate) fn transfer_creator(ctx: Context<CreatorOnly>, new_creator: Pubkey) -> Result<()> {
+    require_keys_neq!(new_creator, Pubkey::default(), LaunchError::InvalidParams);
+    let launch = &mut ctx.accounts.launch_metadata;
+    let old_creator = launch.creator;
+    launch.creator = new_creator;
+
+    emit!(CreatorTransferred {
+        launch_id: launch.launch_id,
+        old_creator,
+        new_creator,
+    });
+    Ok(())
+}
+
+/// Pays out every lamport of creator fees accrued on the launch account.
+pub(crate) fn claim_creator_fees(ctx: Context<ClaimCreatorFees>) -> Result<()> {
+    let launch = &mut ctx.accounts.launch_metadata;
+    require!(launch.creator_fees_accrued > 0, LaunchError::InsufficientFunds);
+
+    let info = launch.to_account_info();
+    let rent = Rent::get()?.minimum_balance(info.data_len());
+    let amount = launch.take_creator_fees(info.lamports(), rent)?;
+    // The launch account is program-owned, so lamports move directly.
+    launch.sub_lamports(amount)?;
+    ctx.accounts.creator.add_lamports(amount)?;
+
+    emit!(CreatorFeesClaimed {
+        launch_id: launch.launch_id,
+        creator:   ctx.accounts.creator.key(),
+        amount,
+    });
+    Ok(())
+}
@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use crate::state::global_state::GlobalState;
+use crate::state::{global_state::GlobalState, launch_policy::PolicyBounds};
 
 #[event]
 pub struct Initialization {
@@ -15,7 +15,7 @@ pub struct Initialize<'info> {
     #[account(
         init,
         payer  = admin,
-        space  = 8 + std::mem::size_of::<GlobalState>(), // 8-byte discriminator + struct size
+        space  = GlobalState::LEN,
         seeds = [b"global_v2"], 
         bump
     )]
@@ -28,7 +28,7 @@ pub struct Initialize<'info> {
     pub rent:           Sysvar<'info, Rent>,
 }
 
-pub fn initialize(
+pub(crate) fn initialize(
     ctx: Context<Initialize>,
     platform_fee_recipient: Pubkey,
     launch_fee_lamports: u64,
@@ -36,9 +36,13 @@ pub fn initialize(
     let global = &mut ctx.accounts.global_state;
 
     global.admin         = ctx.accounts.admin.key();
+    global.pending_admin = Pubkey::default();
     global.platform_fee_recipient = platform_fee_recipient;
     global.launch_fee_lamports = launch_fee_lamports;
     global.launch_count  = 0;
+    global.paused        = false;
+    global.paused_at     = 0;
+    global.policy_bounds = PolicyBounds::default();
     global.bump          = ctx.bumps.global_state;
 
     emit!(Initialization {
@@ -1,95 +1,308 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer, CloseAccount, SetAuthority};
+use anchor_lang::system_program;
 use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::spl_token::instruction::AuthorityType;
+use anchor_spl::token::Token;
+use anchor_spl::token_2022::spl_token_2022::instruction::AuthorityType;
+use anchor_spl::token_interface::{
+    self, Burn, CloseAccount, Mint, SetAuthority, TokenAccount, TokenInterface,
+};
+use sendor_amm::program::SendorAmm;
+use sendor_amm::state::{POOL_SEED, SOL_VAULT_SEED};
+use crate::errors::LaunchError;
+use crate::token_extensions;
 use crate::state::global_state::GlobalState;
 use crate::state::launch_metadata::LaunchMetadata;
 use crate::state::bonding_curve_state::BondingCurveState;
+use crate::state::launch_policy::LaunchPolicy;
+
+/// What left the curve for the pool, so a graduation can be audited against
+/// the curve's recorded reserve.
+#[event]
+pub struct Migrated {
+    pub launch_metadata: Pubkey,
+    pub pool: Pubkey,
+    pub sol_reserve: u64,
+    pub token_amount: u64,
+    pub creator_fees: u64,
+    pub liquidity_burned: u64,
+}
 
 #[derive(Accounts)]
 pub struct Migrate<'info> {
-    #[account(mut, has_one = admin)]
+    #[account(seeds = [b"global_v2"], bump = global_state.bump)]
     pub global_state: Account<'info, GlobalState>,
-    
-    #[account(mut, close = admin, has_one = token_mint, has_one = vault)]
+
+    #[account(mut, close = payer, has_one = token_mint, has_one = vault, has_one = creator)]
     pub launch_metadata: Account<'info, LaunchMetadata>,
-    
-    #[account(mut, close = admin, has_one = launch_metadata)]
+
+    #[account(mut, close = payer, has_one = launch_metadata)]
     pub bonding_curve: Account<'info, BondingCurveState>,
-    
+
+    /// Closed with the launch; the hook lets a graduated token trade freely
+    #[account(
+        mut,
+        close = payer,
+        has_one = launch_metadata,
+        seeds = [b"policy", launch_metadata.launch_id.to_le_bytes().as_ref()],
+        bump = launch_policy.bump
+    )]
+    pub launch_policy: Account<'info, LaunchPolicy>,
+
+    /// Emptied here: the reserve goes to the pool and its rent to the caller
+    #[account(
+        mut,
+        seeds = [b"sol_vault", launch_metadata.launch_id.to_le_bytes().as_ref()],
+        bump = launch_metadata.sol_vault_bump
+    )]
+    pub sol_vault: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, mint::token_program = token_program)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    /// Receives the creator fees still held on the launch, topped up to its
+    /// rent-exempt minimum by the caller if the fees alone fall short
+    #[account(mut)]
+    pub creator: SystemAccount<'info>,
+
+    /// CHECK: created by the AMM; the launch is its creator
+    #[account(
+        mut,
+        seeds = [POOL_SEED, token_mint.key().as_ref(), launch_metadata.key().as_ref()],
+        seeds::program = amm_program.key(),
+        bump
+    )]
+    pub pool: UncheckedAccount<'info>,
+
+    /// CHECK: the pool's SOL vault, funded with the curve's reserve
+    #[account(
+        mut,
+        seeds = [SOL_VAULT_SEED, pool.key().as_ref()],
+        seeds::program = amm_program.key(),
+        bump
+    )]
+    pub pool_sol_vault: UncheckedAccount<'info>,
+
+    /// CHECK: created by the AMM as the pool's token account
     #[account(mut)]
-    pub vault: Account<'info, TokenAccount>,
-    
+    pub pool_token_vault: UncheckedAccount<'info>,
+
+    /// CHECK: created by the AMM
     #[account(mut)]
-    pub token_mint: Account<'info, anchor_spl::token::Mint>,
-    
-    #[account(init_if_needed, payer = admin, 
-             associated_token::mint = token_mint, 
-             associated_token::authority = admin)]
-    pub admin_token_account: Account<'info, TokenAccount>,
-    
+    pub lp_mint: UncheckedAccount<'info>,
+
+    /// CHECK: created by the AMM for the launch; emptied and closed here
     #[account(mut)]
-    pub admin: Signer<'info>,
-    
-    pub token_program: Program<'info, Token>,
+    pub launch_lp_account: UncheckedAccount<'info>,
+
+    /// Anyone may trigger graduation. The caller funds the pool's accounts
+    /// and gets back the rent of the launch accounts closed here.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub amm_program: Program<'info, SendorAmm>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub lp_token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
-pub fn migrate(ctx: Context<Migrate>) -> Result<()> {
-    let launch = &ctx.accounts.launch_metadata;
-    let vault = &ctx.accounts.vault;
-    let token_mint = &ctx.accounts.token_mint;
-    let admin_token_account = &ctx.accounts.admin_token_account;
-
-    let remaining_tokens = vault.amount;
-    if remaining_tokens > 0 {
-        let launch_id_bytes = launch.launch_id.to_le_bytes();
-        let seeds = &[b"launch", launch_id_bytes.as_ref(), &[launch.bump]];
-        let signer_seeds = &[&seeds[..]];
-        
-        token::transfer(
+/// Graduates the launch into a constant-product pool once its threshold is
+/// met: the curve's SOL reserve and every token left in the vault seed the
+/// pool, and the LP tokens the launch receives are burned so the liquidity can
+/// never be pulled. Remaining accounts are forwarded to the mint's transfer
+/// hook.
+pub(crate) fn migrate<'info>(ctx: Context<'_, '_, '_, 'info, Migrate<'info>>) -> Result<()> {
+    let accounts = ctx.accounts;
+    require!(
+        !accounts.launch_metadata.is_paused(&accounts.global_state),
+        LaunchError::Paused
+    );
+
+    let launch_info = accounts.launch_metadata.to_account_info();
+    let launch_rent = Rent::get()?.minimum_balance(launch_info.data_len());
+    let creator_fees = accounts
+        .launch_metadata
+        .take_creator_fees(launch_info.lamports(), launch_rent)?;
+    let launch = &accounts.launch_metadata;
+    let launch_id_bytes = launch.launch_id.to_le_bytes();
+    let seeds: &[&[u8]] = &[b"launch", launch_id_bytes.as_ref(), &[launch.bump]];
+    let curve = &accounts.bonding_curve;
+    let reserve = curve.real_sol_reserves;
+    require!(
+        curve.graduation.is_met(curve.current_supply, reserve),
+        LaunchError::PrematureMigration
+    );
+    require!(reserve > 0, LaunchError::InsufficientLiquidity);
+    curve.check_reserves(&accounts.sol_vault.to_account_info())?;
+    let token_amount = accounts.vault.amount;
+
+    // 1. Pay out the creator's fees, then move the reserve into the pool and
+    // empty the SOL vault. Anything left beyond the reserve is rent and goes
+    // to the caller, as does the launch account's when it closes.
+    //
+    // The runtime rejects a payout leaving an empty wallet below its
+    // rent-exempt minimum, so the caller covers the gap rather than let the
+    // creator's wallet block graduation; the accounts closed here repay it.
+    let creator_info = accounts.creator.to_account_info();
+    let creator_shortfall = if creator_fees > 0 {
+        Rent::get()?
+            .minimum_balance(creator_info.data_len())
+            .saturating_sub(creator_info.lamports().saturating_add(creator_fees))
+    } else {
+        0
+    };
+    if creator_shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: accounts.payer.to_account_info(),
+                    to: creator_info.clone(),
+                },
+            ),
+            creator_shortfall,
+        )?;
+    }
+    launch_info.sub_lamports(creator_fees)?;
+    creator_info.add_lamports(creator_fees)?;
+
+    // The AMM counts only lamports above the pool vault's rent-exempt minimum
+    // as reserve, so the caller funds that rent and the whole curve reserve
+    // becomes liquidity.
+    let pool_vault_rent = Rent::get()?
+        .minimum_balance(0)
+        .saturating_sub(accounts.pool_sol_vault.lamports());
+    if pool_vault_rent > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: accounts.payer.to_account_info(),
+                    to: accounts.pool_sol_vault.to_account_info(),
+                },
+            ),
+            pool_vault_rent,
+        )?;
+    }
+
+    let sol_vault_seeds: &[&[u8]] =
+        &[b"sol_vault", launch_id_bytes.as_ref(), &[launch.sol_vault_bump]];
+    let leftover = accounts.sol_vault.lamports() - reserve;
+    for (to, lamports) in [
+        (accounts.pool_sol_vault.to_account_info(), reserve),
+        (accounts.payer.to_account_info(), leftover),
+    ] {
+        system_program::transfer(
             CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: vault.to_account_info(),
-                    to: admin_token_account.to_account_info(),
-                    authority: launch.to_account_info(),
+                accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: accounts.sol_vault.to_account_info(),
+                    to,
                 },
-                signer_seeds,
+                &[sol_vault_seeds],
             ),
-            remaining_tokens,
+            lamports,
         )?;
     }
 
-    let launch_id_bytes = launch.launch_id.to_le_bytes();
-    let seeds = &[b"launch", launch_id_bytes.as_ref(), &[launch.bump]];
-    
-    token::set_authority(
+    // 2. Open the pool with the remaining supply.
+    sendor_amm::cpi::initialize_pool(
         CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            SetAuthority {
-                account_or_mint: token_mint.to_account_info(),
-                current_authority: launch.to_account_info(),
+            accounts.amm_program.to_account_info(),
+            sendor_amm::cpi::accounts::InitializePool {
+                pool: accounts.pool.to_account_info(),
+                creator: launch_info.clone(),
+                sol_vault: accounts.pool_sol_vault.to_account_info(),
+                token_mint: accounts.token_mint.to_account_info(),
+                token_vault: accounts.pool_token_vault.to_account_info(),
+                creator_token_account: accounts.vault.to_account_info(),
+                lp_mint: accounts.lp_mint.to_account_info(),
+                creator_lp_account: accounts.launch_lp_account.to_account_info(),
+                payer: accounts.payer.to_account_info(),
+                token_program: accounts.token_program.to_account_info(),
+                lp_token_program: accounts.lp_token_program.to_account_info(),
+                associated_token_program: accounts.associated_token_program.to_account_info(),
+                system_program: accounts.system_program.to_account_info(),
             },
-            &[&seeds[..]],
-        ),
-        AuthorityType::FreezeAccount,
-        None,
+            &[seeds],
+        )
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+        token_amount,
     )?;
 
-    token::close_account(
+    // 3. Burn the launch's LP tokens and reclaim the account's rent.
+    let liquidity = {
+        let data = accounts.launch_lp_account.try_borrow_data()?;
+        TokenAccount::try_deserialize(&mut &data[..])?.amount
+    };
+    let lp_program = accounts.lp_token_program.to_account_info();
+    token_interface::burn(
         CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            CloseAccount {
-                account: vault.to_account_info(),
-                destination: ctx.accounts.admin.to_account_info(),
-                authority: launch.to_account_info(),
+            lp_program.clone(),
+            Burn {
+                mint: accounts.lp_mint.to_account_info(),
+                from: accounts.launch_lp_account.to_account_info(),
+                authority: launch_info.clone(),
             },
-            &[&seeds[..]],
+            &[seeds],
         ),
+        liquidity,
     )?;
-    
+    token_interface::close_account(CpiContext::new_with_signer(
+        lp_program,
+        CloseAccount {
+            account: accounts.launch_lp_account.to_account_info(),
+            destination: accounts.payer.to_account_info(),
+            authority: launch_info.clone(),
+        },
+        &[seeds],
+    ))?;
+
+    // 4. Release the mint, so the supply is fixed and no holder can be
+    // frozen, and close the emptied vault.
+    let token_program = accounts.token_program.to_account_info();
+    for authority_type in [AuthorityType::MintTokens, AuthorityType::FreezeAccount] {
+        token_interface::set_authority(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                SetAuthority {
+                    account_or_mint: accounts.token_mint.to_account_info(),
+                    current_authority: launch_info.clone(),
+                },
+                &[seeds],
+            ),
+            authority_type,
+            None,
+        )?;
+    }
+
+    token_extensions::harvest_withheld(
+        token_program.clone(),
+        accounts.token_mint.to_account_info(),
+        accounts.vault.to_account_info(),
+    )?;
+    token_interface::close_account(CpiContext::new_with_signer(
+        token_program,
+        CloseAccount {
+            account: accounts.vault.to_account_info(),
+            destination: accounts.payer.to_account_info(),
+            authority: launch_info,
+        },
+        &[seeds],
+    ))?;
+
+    emit!(Migrated {
+        launch_metadata: accounts.launch_metadata.key(),
+        pool: accounts.pool.key(),
+        sol_reserve: reserve,
+        token_amount,
+        creator_fees,
+        liquidity_burned: liquidity,
+    });
+
     Ok(())
 }
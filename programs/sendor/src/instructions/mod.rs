@@ -3,6 +3,12 @@ pub mod create_launch;
 pub mod buy;
 pub mod sell;
 pub mod transfer;
-pub mod update_global;
 pub mod set_sell_window;
 pub mod migrate;
+pub mod set_trade_fees;
+pub mod creator_controls;
+pub mod admin_controls;
+pub mod randomness;
+pub mod allowance;
+pub mod realloc_launch;
+pub mod vesting;
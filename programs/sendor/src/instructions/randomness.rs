@@ -0,0 +1,134 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::{hash, hashv};
+use anchor_lang::solana_program::sysvar::slot_hashes;
+use crate::errors::LaunchError;
+use crate::state::{global_state::GlobalState, randomness_oracle::RandomnessOracle};
+
+#[event]
+pub struct RandomnessCommitted {
+    pub round:      u64,
+    pub commitment: [u8; 32],
+}
+
+#[event]
+pub struct RandomnessRevealed {
+    pub round: u64,
+    pub value: [u8; 32],
+}
+
+#[derive(Accounts)]
+pub struct InitRandomnessOracle<'info> {
+    #[account(has_one = admin)]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = RandomnessOracle::LEN,
+        seeds = [b"randomness"],
+        bump
+    )]
+    pub randomness: Account<'info, RandomnessOracle>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetRandomnessAuthority<'info> {
+    #[account(has_one = admin)]
+    pub global_state: Account<'info, GlobalState>,
+    #[account(mut, seeds = [b"randomness"], bump = randomness.bump)]
+    pub randomness: Account<'info, RandomnessOracle>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct OracleRound<'info> {
+    #[account(mut, seeds = [b"randomness"], bump = randomness.bump, has_one = authority)]
+    pub randomness: Account<'info, RandomnessOracle>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RevealRandomness<'info> {
+    #[account(mut, seeds = [b"randomness"], bump = randomness.bump, has_one = authority)]
+    pub randomness: Account<'info, RandomnessOracle>,
+    pub authority: Signer<'info>,
+    /// CHECK: the SlotHashes sysvar, read raw since it is too large to
+    /// deserialize
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+}
+
+pub(crate) fn init_randomness_oracle(ctx: Context<InitRandomnessOracle>, authority: Pubkey) -> Result<()> {
+    let oracle = &mut ctx.accounts.randomness;
+    oracle.authority = authority;
+    oracle.commitment = [0; 32];
+    oracle.committed_slot = 0;
+    oracle.pending = false;
+    oracle.value = [0; 32];
+    oracle.revealed_at = 0;
+    oracle.round = 0;
+    oracle.bump = ctx.bumps.randomness;
+    Ok(())
+}
+
+pub(crate) fn set_randomness_authority(ctx: Context<SetRandomnessAuthority>, authority: Pubkey) -> Result<()> {
+    let oracle = &mut ctx.accounts.randomness;
+    oracle.authority = authority;
+    // A commitment made by the old key can no longer be revealed.
+    oracle.pending = false;
+    Ok(())
+}
+
+pub(crate) fn commit_randomness(ctx: Context<OracleRound>, commitment: [u8; 32]) -> Result<()> {
+    let oracle = &mut ctx.accounts.randomness;
+    require!(!oracle.pending, LaunchError::RandomnessPending);
+
+    oracle.commitment = commitment;
+    oracle.committed_slot = Clock::get()?.slot;
+    oracle.pending = true;
+
+    emit!(RandomnessCommitted {
+        round: oracle.round + 1,
+        commitment,
+    });
+    Ok(())
+}
+
+/// Reveals the secret behind the pending commitment. It must land in a later
+/// slot than the commit, and is folded into every earlier value so a single
+/// round cannot be chosen in isolation. The most recent slot hash is mixed in
+/// too, so the authority cannot grind secrets offline for a value it likes.
+pub(crate) fn reveal_randomness(ctx: Context<RevealRandomness>, secret: [u8; 32]) -> Result<()> {
+    let oracle = &mut ctx.accounts.randomness;
+    let clock = Clock::get()?;
+    require!(oracle.pending, LaunchError::InvalidRandomness);
+    require!(clock.slot > oracle.committed_slot, LaunchError::InvalidRandomness);
+    require!(hash(&secret).to_bytes() == oracle.commitment, LaunchError::InvalidRandomness);
+
+    let slot_hash = latest_slot_hash(&ctx.accounts.slot_hashes)?;
+    oracle.value = hashv(&[&oracle.value, &secret, &slot_hash, &clock.slot.to_le_bytes()]).to_bytes();
+    oracle.revealed_at = clock.unix_timestamp;
+    oracle.round = oracle.round.checked_add(1).ok_or(LaunchError::MathOverflow)?;
+    oracle.pending = false;
+
+    emit!(RandomnessRevealed {
+        round: oracle.round,
+        value: oracle.value,
+    });
+    Ok(())
+}
+
+/// Hash of the most recent slot in the SlotHashes sysvar, laid out as a u64
+/// entry count followed by `(slot, hash)` pairs, newest first.
+fn latest_slot_hash(slot_hashes: &AccountInfo) -> Result<[u8; 32]> {
+    let data = slot_hashes.try_borrow_data()?;
+    let entry = data.get(16..48).ok_or(LaunchError::InvalidRandomness)?;
+    let mut slot_hash = [0u8; 32];
+    slot_hash.copy_from_slice(entry);
+    Ok(slot_hash)
+}
@@ -0,0 +1,148 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token_interface::Mint;
+use crate::errors::LaunchError;
+use crate::state::{
+    bonding_curve_state::BondingCurveState,
+    global_state::GlobalState,
+    launch_metadata::LaunchMetadata,
+    launch_policy::{LaunchPolicy, PolicyBounds},
+    window_schedule::WindowSchedule,
+};
+
+#[event]
+pub struct LaunchMigrated {
+    pub launch_id:         u64,
+    pub creator:           Pubkey,
+    pub real_sol_reserves: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(launch_id: u64)]
+pub struct ReallocLaunch<'info> {
+    #[account(has_one = admin)]
+    pub global_state: Account<'info, GlobalState>,
+
+    /// CHECK: an older layout that no longer deserializes; the address is
+    /// pinned by seeds and the owner and discriminator are checked here
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [b"launch", launch_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub launch_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: as above
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [b"bonding", launch_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub bonding_curve: UncheckedAccount<'info>,
+
+    /// The original launches had no policy of their own
+    #[account(
+        init,
+        payer = admin,
+        space = LaunchPolicy::LEN,
+        seeds = [b"policy", launch_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub launch_policy: Account<'info, LaunchPolicy>,
+
+    /// Takes over the curve's SOL, which the original layout kept in the
+    /// launch account itself
+    #[account(
+        mut,
+        seeds = [b"sol_vault", launch_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub sol_vault: SystemAccount<'info>,
+
+    /// Must be the mint recorded in the old layout
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    /// Pays the extra rent and takes the creator role, which the original
+    /// layout never recorded
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Rewrites a launch created under the original layout into the current one.
+/// The curve keeps its prices and supply sold, its SOL moves to the launch's
+/// `sol_vault`, and the launch gets a policy with the protocol's original
+/// rules. Run `realloc_global` first.
+pub(crate) fn realloc_launch(ctx: Context<ReallocLaunch>, launch_id: u64) -> Result<()> {
+    let accounts = &ctx.accounts;
+    let launch_info = accounts.launch_metadata.to_account_info();
+    let curve_info = accounts.bonding_curve.to_account_info();
+    let rent = Rent::get()?;
+
+    // Whatever the old launch account holds beyond its rent backs the tokens sold.
+    let reserves = launch_info
+        .lamports()
+        .saturating_sub(rent.minimum_balance(LaunchMetadata::LEGACY_LEN));
+    let launch = LaunchMetadata::from_legacy(
+        &launch_info.try_borrow_data()?,
+        accounts.admin.key(),
+        ctx.bumps.sol_vault,
+    )?;
+    require!(launch.launch_id == launch_id, LaunchError::InvalidParams);
+    require_keys_eq!(launch.token_mint, accounts.token_mint.key(), LaunchError::InvalidParams);
+    let curve = BondingCurveState::from_legacy(
+        &curve_info.try_borrow_data()?,
+        accounts.token_mint.supply,
+        reserves,
+    )?;
+    require_keys_eq!(curve.launch_metadata, launch_info.key(), LaunchError::InvalidParams);
+
+    let sol_vault = accounts.sol_vault.to_account_info();
+    let top_ups = [
+        (&launch_info, LaunchMetadata::LEN, launch_info.lamports() - reserves),
+        (&curve_info, BondingCurveState::LEN, curve_info.lamports()),
+        (&sol_vault, 0, sol_vault.lamports()),
+    ];
+    for (to, len, held) in top_ups {
+        let top_up = rent.minimum_balance(len).saturating_sub(held);
+        if top_up > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: accounts.admin.to_account_info(),
+                        to:   to.clone(),
+                    },
+                ),
+                top_up,
+            )?;
+        }
+    }
+    launch_info.sub_lamports(reserves)?;
+    sol_vault.add_lamports(reserves)?;
+
+    launch_info.realloc(LaunchMetadata::LEN, true)?;
+    launch.try_serialize(&mut &mut launch_info.try_borrow_mut_data()?[..])?;
+    curve_info.realloc(BondingCurveState::LEN, true)?;
+    curve.try_serialize(&mut &mut curve_info.try_borrow_mut_data()?[..])?;
+
+    let rules = PolicyBounds::default();
+    let policy = &mut ctx.accounts.launch_policy;
+    policy.launch_metadata = launch_info.key();
+    policy.launch_id = launch_id;
+    policy.sell_limit_percent = rules.max_sell_limit_percent;
+    policy.transfer_limit_percent = rules.max_transfer_limit_percent;
+    policy.actions_per_day = rules.max_actions_per_day;
+    policy.schedule = WindowSchedule::spread(rules.max_windows_per_day, rules.max_window_duration)?;
+    policy.bump = ctx.bumps.launch_policy;
+
+    emit!(LaunchMigrated {
+        launch_id,
+        creator: launch.creator,
+        real_sol_reserves: reserves,
+    });
+    Ok(())
+}
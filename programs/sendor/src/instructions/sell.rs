@@ -1,10 +1,17 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
-use anchor_spl::token::{self, Token, TokenAccount, TransferChecked};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::constants::{EMERGENCY_EXIT_DELAY, TRANSFER_HOOK_PROGRAM_ID};
 use crate::errors::LaunchError;
+use crate::token_extensions;
+use crate::transfer_hook;
 use crate::state::{
     bonding_curve_state::BondingCurveState,
+    global_state::GlobalState,
     launch_metadata::LaunchMetadata,
+    launch_policy::{Allowance, LaunchPolicy},
+    randomness_oracle::RandomnessOracle,
+    trade_fees::{FeeSplit, TradeFees},
     user_record::UserRecord,
 };
 
@@ -13,18 +20,40 @@ pub struct SellEvent {
     pub seller: Pubkey,
     pub qty: u64,
     pub payout: u64,
+    pub protocol_fee: u64,
+    pub creator_fee: u64,
+}
+
+/// What a holder may still sell or transfer before their periods reset.
+#[event]
+pub struct AllowanceUpdated {
+    pub user: Pubkey,
+    pub launch_metadata: Pubkey,
+    pub allowance: Allowance,
 }
 
 #[derive(Accounts)]
 pub struct Sell<'info> {
+    #[account(seeds = [b"global_v2"], bump = global_state.bump)]
+    pub global_state: Account<'info, GlobalState>,
     #[account(mut, has_one = vault, has_one = token_mint)]
     pub launch_metadata: Account<'info, LaunchMetadata>,
     #[account(mut, has_one = launch_metadata)]
     pub bonding_curve: Account<'info, BondingCurveState>,
+    #[account(
+        mut,
+        seeds = [b"sol_vault", launch_metadata.launch_id.to_le_bytes().as_ref()],
+        bump = launch_metadata.sol_vault_bump
+    )]
+    pub sol_vault: SystemAccount<'info>,
+    #[account(mut, has_one = launch_metadata)]
+    pub launch_policy: Account<'info, LaunchPolicy>,
+    #[account(seeds = [b"randomness"], bump = randomness.bump)]
+    pub randomness: Account<'info, RandomnessOracle>,
+    #[account(mut, mint::token_program = token_program)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
     #[account(mut)]
-    pub token_mint: Account<'info, anchor_spl::token::Mint>,
-    #[account(mut)]
-    pub vault: Account<'info, TokenAccount>,
+    pub vault: InterfaceAccount<'info, TokenAccount>,
     #[account(mut)]
     pub seller: Signer<'info>,
     #[account(
@@ -32,7 +61,7 @@ pub struct Sell<'info> {
         constraint = seller_token_account.owner == seller.key(),
         constraint = seller_token_account.mint == token_mint.key()
     )]
-    pub seller_token_account: Account<'info, TokenAccount>,
+    pub seller_token_account: InterfaceAccount<'info, TokenAccount>,
     #[account(
         init_if_needed,
         payer  = seller,
@@ -41,77 +70,216 @@ pub struct Sell<'info> {
         bump
     )]
     pub user_record: Account<'info, UserRecord>,
-    pub token_program: Program<'info, Token>,
+    /// CHECK: the seller's record in the hook, read for the allowance event
+    /// when the mint has the transfer hook
+    #[account(
+        seeds = [b"record", token_mint.key().as_ref(), seller.key().as_ref()],
+        seeds::program = TRANSFER_HOOK_PROGRAM_ID,
+        bump
+    )]
+    pub transfer_record: Option<UncheckedAccount<'info>>,
+    #[account(mut, address = global_state.platform_fee_recipient @ LaunchError::InvalidFeeRecipient)]
+    pub platform_fee_recipient: SystemAccount<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
-pub fn sell(ctx: Context<Sell>, amount: u64, min_payout: u64) -> Result<()> {
-    let launch = &mut ctx.accounts.launch_metadata;
-    let curve = &mut ctx.accounts.bonding_curve;
-    let seller = &ctx.accounts.seller;
-    let seller_account = &ctx.accounts.seller_token_account;
-    let record = &mut ctx.accounts.user_record;
+pub(crate) fn sell<'info>(ctx: Context<'_, '_, '_, 'info, Sell<'info>>, amount: u64, min_payout: u64) -> Result<()> {
+    let accounts = ctx.accounts;
+    accounts.check_daily_limit(amount)?;
+
+    let received = accounts.received(amount)?;
+    let gross = accounts.bonding_curve.payout_to_sell(received)?;
+    let fees = accounts.fees().charge(gross);
+    let payout = gross - fees.total();
+    require!(payout >= min_payout, LaunchError::PayoutTooLow);
+
+    accounts.settle(amount, received, payout, fees, ctx.remaining_accounts)
+}
 
-    require!(curve.decimals <= 18, LaunchError::InvalidDecimals);
+/// Sells just enough tokens to pay out at least `lamports_out` after fees and
+/// returns the token amount taken, including any Token-2022 transfer fee.
+/// Tokens sell in whole base units and fees floor, so the payout may land a
+/// little above `lamports_out`; it is never below.
+pub(crate) fn sell_exact_out<'info>(
+    ctx: Context<'_, '_, '_, 'info, Sell<'info>>,
+    lamports_out: u64,
+    max_tokens_in: u64,
+) -> Result<u64> {
+    let accounts = ctx.accounts;
 
-    let now = Clock::get()?.unix_timestamp;
-    require!(launch.is_window_open(now), LaunchError::NotInTradingWindow);
+    let received = accounts.bonding_curve.min_sell_for_net(&accounts.fees(), lamports_out)?;
+    let amount = token_extensions::gross_up(&accounts.token_mint.to_account_info(), received)?;
+    require!(amount <= max_tokens_in, LaunchError::SlippageExceeded);
+    accounts.check_daily_limit(amount)?;
 
-    let today = (now / 86_400) as u64;
-    require!(record.last_action_day != today, LaunchError::ActionAlreadyPerformed);
+    // Pay out what the curve releases net of fees, never more.
+    let gross = accounts.bonding_curve.payout_to_sell(received)?;
+    let fees = accounts.fees().charge(gross);
+    let payout = gross - fees.total();
+    require!(payout >= lamports_out, LaunchError::PayoutTooLow);
+    accounts.settle(amount, received, payout, fees, ctx.remaining_accounts)?;
+    Ok(amount)
+}
 
-    let balance = seller_account.amount;
-    let max_sell = balance.checked_mul(10).ok_or(LaunchError::MathOverflow)? / 100;
-    require!(amount <= max_sell, LaunchError::ExceedsSellLimit);
+/// Exit path while trading is halted. Once a pause (global, admin or creator)
+/// has lasted `EMERGENCY_EXIT_DELAY`, holders may sell at the curve price
+/// outside the trading windows and without the daily cap, so a pause that is
+/// never lifted cannot trap their funds.
+pub(crate) fn emergency_sell<'info>(
+    ctx: Context<'_, '_, '_, 'info, Sell<'info>>,
+    amount: u64,
+    min_payout: u64,
+) -> Result<()> {
+    let accounts = ctx.accounts;
+    let now = Clock::get()?.unix_timestamp;
+    let paused_since = accounts
+        .launch_metadata
+        .paused_since(&accounts.global_state)
+        .ok_or(LaunchError::EmergencyExitUnavailable)?;
+    require!(
+        now.saturating_sub(paused_since) >= EMERGENCY_EXIT_DELAY,
+        LaunchError::EmergencyExitUnavailable
+    );
+    require!(amount <= accounts.seller_token_account.amount, LaunchError::InsufficientFunds);
 
-    let payout = curve.compute_payout(amount)?;
+    let received = accounts.received(amount)?;
+    let gross = accounts.bonding_curve.payout_to_sell(received)?;
+    let fees = accounts.fees().charge(gross);
+    let payout = gross - fees.total();
     require!(payout >= min_payout, LaunchError::PayoutTooLow);
 
-    let launch_lamports = **launch.to_account_info().lamports.borrow();
-    require!(payout <= launch_lamports, LaunchError::InsufficientLiquidity);
-
-    token::transfer_checked(
-        CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            TransferChecked {
-                from: seller_account.to_account_info(),
-                to: ctx.accounts.vault.to_account_info(),
-                authority: seller.to_account_info(),
-                mint: ctx.accounts.token_mint.to_account_info(),
-            },
-        ),
-        amount,
-        curve.decimals,
-    )?;
-
-    let id_bytes = launch.launch_id.to_le_bytes();
-    let seeds: &[&[u8]] = &[b"launch", id_bytes.as_ref(), &[launch.bump]];
-    system_program::transfer(
-        CpiContext::new_with_signer(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: launch.to_account_info(),
-                to: seller.to_account_info(),
-            },
-            &[seeds],
-        ),
-        payout,
-    )?;
-
-    curve.current_supply = curve
-        .current_supply
-        .checked_sub(amount)
-        .ok_or(LaunchError::MathOverflow)?;
-    record.last_action_day = today;
-    if record.user == Pubkey::default() {
-        record.user = seller.key();
+    accounts.settle(amount, received, payout, fees, ctx.remaining_accounts)
+}
+
+impl<'info> Sell<'info> {
+    fn fees(&self) -> TradeFees {
+        self.launch_metadata.trade_fees(&self.global_state.trade_fees)
+    }
+
+    /// Tokens the vault ends up with when `amount` is sent, net of any
+    /// transfer fee. Sells are priced on this so the vault always backs the
+    /// curve's supply.
+    fn received(&self, amount: u64) -> Result<u64> {
+        let fee = token_extensions::transfer_fee(&self.token_mint.to_account_info(), amount)?;
+        Ok(amount - fee)
+    }
+
+    /// Enforces the trading window and the holder's trailing 24h action count
+    /// and sell volume, capped against the balance snapshotted when the
+    /// window last started from empty.
+    fn check_daily_limit(&mut self, amount: u64) -> Result<()> {
+        require!(!self.launch_metadata.is_paused(&self.global_state), LaunchError::Paused);
+        require!(self.bonding_curve.decimals <= 18, LaunchError::InvalidDecimals);
+
+        let now = Clock::get()?.unix_timestamp;
+        self.launch_policy.roll_windows(&self.randomness, now)?;
+        let policy = &self.launch_policy;
+        require!(policy.schedule.is_open(now), LaunchError::NotInTradingWindow);
+        let limit = self.user_record.sell.at(now, self.seller_token_account.amount);
+        require!(limit.action_count() < policy.actions_per_day, LaunchError::ActionAlreadyPerformed);
+
+        let allowance = limit.remaining(policy.max_sell(limit.snapshot_balance)?);
+        require!(amount <= allowance, LaunchError::ExceedsSellLimit);
+        Ok(())
     }
 
-    emit!(SellEvent {
-        seller: seller.key(),
-        qty: amount,
-        payout,
-    });
-    Ok(())
+    /// Moves `amount` tokens back to the vault, of which `received` arrive,
+    /// pays `payout` lamports to the seller and the protocol fee out of the SOL
+    /// vault, moves the creator fee onto the launch account and records the
+    /// action against the holder's sell allowance. `hook_accounts` are forwarded to the mint's transfer hook.
+    fn settle(
+        &mut self,
+        amount: u64,
+        received: u64,
+        payout: u64,
+        fees: FeeSplit,
+        hook_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let launch = &mut self.launch_metadata;
+        let curve  = &mut self.bonding_curve;
+        let seller = &self.seller;
+        let record = &mut self.user_record;
+        let now    = Clock::get()?.unix_timestamp;
+
+        // The creator's cut moves from the reserve into the accrued fees.
+        let released = payout.checked_add(fees.total()).ok_or(LaunchError::MathOverflow)?;
+        let vault_rent = Rent::get()?.minimum_balance(self.sol_vault.data_len());
+        curve.release(released, self.sol_vault.lamports(), vault_rent)?;
+
+        transfer_hook::transfer_checked(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.seller_token_account.to_account_info(),
+                    to: self.vault.to_account_info(),
+                    authority: seller.to_account_info(),
+                    mint: self.token_mint.to_account_info(),
+                },
+            )
+            .with_remaining_accounts(hook_accounts.to_vec()),
+            amount,
+            curve.decimals,
+        )?;
+
+        let id_bytes = launch.launch_id.to_le_bytes();
+        let seeds: &[&[u8]] = &[b"sol_vault", id_bytes.as_ref(), &[launch.sol_vault_bump]];
+        for (to, lamports) in [
+            (seller.to_account_info(), payout),
+            (self.platform_fee_recipient.to_account_info(), fees.protocol),
+            (launch.to_account_info(), fees.creator),
+        ] {
+            if lamports == 0 {
+                continue;
+            }
+            system_program::transfer(
+                CpiContext::new_with_signer(
+                    self.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: self.sol_vault.to_account_info(),
+                        to,
+                    },
+                    &[seeds],
+                ),
+                lamports,
+            )?;
+        }
+
+        launch.creator_fees_accrued = launch
+            .creator_fees_accrued
+            .checked_add(fees.creator)
+            .ok_or(LaunchError::MathOverflow)?;
+        curve.current_supply = curve
+            .current_supply
+            .checked_sub(received)
+            .ok_or(LaunchError::MathOverflow)?;
+        curve.check_reserves(&self.sol_vault.to_account_info())?;
+        record.record_sell(
+            seller.key(),
+            now,
+            self.seller_token_account.amount,
+            amount,
+        )?;
+
+        emit!(SellEvent {
+            seller: seller.key(),
+            qty: amount,
+            payout,
+            protocol_fee: fees.protocol,
+            creator_fee: fees.creator,
+        });
+        let balance = self.seller_token_account.amount.saturating_sub(amount);
+        let transfer = transfer_hook::transfer_usage(
+            &self.token_mint.to_account_info(),
+            self.transfer_record.as_ref().map(|r| r.as_ref()),
+            record.transfer,
+        )?;
+        emit!(AllowanceUpdated {
+            user: seller.key(),
+            launch_metadata: launch.key(),
+            allowance: self.launch_policy.allowance(&record.sell, &transfer, now, balance)?,
+        });
+        Ok(())
+    }
 }
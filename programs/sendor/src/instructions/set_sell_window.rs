@@ -1,18 +1,23 @@
 use anchor_lang::prelude::*;
 use crate::errors::LaunchError;
-use crate::state::{global_state::GlobalState, launch_metadata::LaunchMetadata};
-
-const DAY: i64 = 86_400;
-const WIN: i64 = 900;
-const MIN_GAP: i64 = 43_200;  // 12 h
-const MAX_GAP: i64 = 64_800;  // 18 h
+use crate::state::{
+    global_state::GlobalState, launch_policy::LaunchPolicy, randomness_oracle::RandomnessOracle,
+    window_schedule::{day_of, WindowSchedule},
+};
 
 #[event]
 pub struct SellWindowSet {
     pub launch_id: u64,
     pub day: u64,
-    pub w1: i64,
-    pub w2: i64,
+    pub windows: Vec<i64>,
+}
+
+#[event]
+pub struct WindowScheduleSet {
+    pub launch_id: u64,
+    pub count: u8,
+    pub duration: i64,
+    pub min_gap: i64,
 }
 
 #[derive(Accounts)]
@@ -20,28 +25,53 @@ pub struct RandomizeSellWindow<'info> {
     #[account(has_one = admin)]
     pub global_state: Account<'info, GlobalState>,
     #[account(mut)]
-    pub launch_metadata: Account<'info, LaunchMetadata>,
+    pub launch_policy: Account<'info, LaunchPolicy>,
+    #[account(seeds = [b"randomness"], bump = randomness.bump)]
+    pub randomness: Account<'info, RandomnessOracle>,
     pub admin: Signer<'info>,
 }
 
-pub fn randomize_sell_window(ctx: Context<RandomizeSellWindow>) -> Result<()> {
-    let launch = &mut ctx.accounts.launch_metadata;
+#[derive(Accounts)]
+pub struct SetWindowSchedule<'info> {
+    #[account(has_one = admin)]
+    pub global_state: Account<'info, GlobalState>,
+    #[account(mut)]
+    pub launch_policy: Account<'info, LaunchPolicy>,
+    pub admin: Signer<'info>,
+}
 
-    let seed = Clock::get()?.slot.wrapping_add(launch.launch_id);
-    let rnd1 = (seed % ((DAY - MAX_GAP - WIN) as u64)) as i64;
-    let gap  = MIN_GAP + ((seed >> 8) % ((MAX_GAP - MIN_GAP) as u64)) as i64;
-    let rnd2 = rnd1 + gap;
-    require!(rnd2 + WIN <= DAY, LaunchError::InvalidWindowTimes);
+pub(crate) fn randomize_sell_window(ctx: Context<RandomizeSellWindow>) -> Result<()> {
+    let policy = &mut ctx.accounts.launch_policy;
+    let now    = Clock::get()?.unix_timestamp;
+    require!(ctx.accounts.randomness.is_fresh(now), LaunchError::RandomnessStale);
 
-    launch.current_day = launch.current_day.checked_add(1).ok_or(LaunchError::MathOverflow)?;
-    launch.window1_start = rnd1;
-    launch.window2_start = rnd2;
+    policy.draw_windows(&ctx.accounts.randomness, day_of(now))?;
 
     emit!(SellWindowSet {
-        launch_id: launch.launch_id,
-        day: launch.current_day,
-        w1: rnd1,
-        w2: rnd2,
+        launch_id: policy.launch_id,
+        day: policy.schedule.day,
+        windows: policy.schedule.windows().to_vec(),
+    });
+    Ok(())
+}
+
+/// Reshapes the launch's windows. Trading stays closed until the next draw,
+/// which happens on the first buy, sell or transfer once the oracle can seed
+/// it or via an admin roll.
+pub(crate) fn set_window_schedule(
+    ctx: Context<SetWindowSchedule>,
+    count: u8,
+    duration: i64,
+    min_gap: i64,
+) -> Result<()> {
+    let policy = &mut ctx.accounts.launch_policy;
+    policy.schedule = WindowSchedule::new(count, duration, min_gap)?;
+
+    emit!(WindowScheduleSet {
+        launch_id: policy.launch_id,
+        count,
+        duration,
+        min_gap,
     });
     Ok(())
 }
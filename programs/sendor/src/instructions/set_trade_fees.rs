@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+use crate::state::{global_state::GlobalState, launch_metadata::LaunchMetadata, trade_fees::TradeFees};
+
+#[event]
+pub struct TradeFeesUpdated {
+    pub protocol_bps: u16,
+    pub creator_bps:  u16,
+}
+
+#[event]
+pub struct LaunchFeesUpdated {
+    pub launch_id:    u64,
+    pub fee_override: Option<TradeFees>,
+}
+
+#[derive(Accounts)]
+pub struct SetTradeFees<'info> {
+    #[account(mut, has_one = admin)]
+    pub global_state: Account<'info, GlobalState>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetLaunchFees<'info> {
+    #[account(has_one = admin)]
+    pub global_state: Account<'info, GlobalState>,
+    #[account(mut)]
+    pub launch_metadata: Account<'info, LaunchMetadata>,
+    pub admin: Signer<'info>,
+}
+
+pub(crate) fn set_trade_fees(ctx: Context<SetTradeFees>, fees: TradeFees) -> Result<()> {
+    fees.validate()?;
+    ctx.accounts.global_state.trade_fees = fees;
+
+    emit!(TradeFeesUpdated {
+        protocol_bps: fees.protocol_bps,
+        creator_bps:  fees.creator_bps,
+    });
+    Ok(())
+}
+
+/// Sets or clears (`None`) the per-launch fee override.
+pub(crate) fn set_launch_fees(ctx: Context<SetLaunchFees>, fee_override: Option<TradeFees>) -> Result<()> {
+    if let Some(fees) = fee_override {
+        fees.validate()?;
+    }
+    let launch = &mut ctx.accounts.launch_metadata;
+    launch.fee_override = fee_override;
+
+    emit!(LaunchFeesUpdated {
+        launch_id: launch.launch_id,
+        fee_override,
+    });
+    Ok(())
+}
@@ -1,8 +1,15 @@
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer as SplTransfer};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::constants::TRANSFER_HOOK_PROGRAM_ID;
 use crate::errors::LaunchError;
-use crate::state::{launch_metadata::LaunchMetadata, user_record::UserRecord};
+use crate::instructions::sell::AllowanceUpdated;
+use crate::token_extensions;
+use crate::transfer_hook::{self, InitTransferRecord};
+use crate::state::{
+    global_state::GlobalState, launch_metadata::LaunchMetadata, launch_policy::LaunchPolicy,
+    randomness_oracle::RandomnessOracle, user_record::UserRecord,
+};
 
 #[event]
 pub struct TransferEvent {
@@ -13,11 +20,20 @@ pub struct TransferEvent {
 
 #[derive(Accounts)]
 pub struct TransferTokens<'info> {
+    #[account(seeds = [b"global_v2"], bump = global_state.bump)]
+    pub global_state: Account<'info, GlobalState>,
+
     #[account(has_one = token_mint)]
     pub launch_metadata: Account<'info, LaunchMetadata>,
 
-    #[account(mut)]
-    pub token_mint: Account<'info, anchor_spl::token::Mint>,
+    #[account(mut, has_one = launch_metadata)]
+    pub launch_policy: Account<'info, LaunchPolicy>,
+
+    #[account(seeds = [b"randomness"], bump = randomness.bump)]
+    pub randomness: Account<'info, RandomnessOracle>,
+
+    #[account(mut, mint::token_program = token_program)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
 
     #[account(mut)]
     pub from: Signer<'info>,
@@ -27,15 +43,16 @@ pub struct TransferTokens<'info> {
         constraint = source_token_account.owner == from.key(),
         constraint = source_token_account.mint  == token_mint.key()
     )]
-    pub source_token_account: Account<'info, TokenAccount>,
+    pub source_token_account: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         init_if_needed,
         payer  = from,
         associated_token::mint      = token_mint,
-        associated_token::authority = to
+        associated_token::authority = to,
+        associated_token::token_program = token_program
     )]
-    pub destination_token_account: Account<'info, TokenAccount>,
+    pub destination_token_account: InterfaceAccount<'info, TokenAccount>,
 
     /// CHECK: arbitrary receiver
     pub to: UncheckedAccount<'info>,
@@ -49,48 +66,106 @@ pub struct TransferTokens<'info> {
     )]
     pub user_record: Account<'info, UserRecord>,
 
-    pub token_program:            Program<'info, Token>,
+    /// CHECK: the sender's record in the hook, opened here on first use; only
+    /// needed with the transfer hook
+    #[account(
+        mut,
+        seeds = [b"record", token_mint.key().as_ref(), from.key().as_ref()],
+        seeds::program = TRANSFER_HOOK_PROGRAM_ID,
+        bump
+    )]
+    pub transfer_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: pinned to the known hook program
+    #[account(executable, address = TRANSFER_HOOK_PROGRAM_ID)]
+    pub transfer_hook_program: Option<UncheckedAccount<'info>>,
+
+    pub token_program:            Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program:           Program<'info, System>,
     pub rent:                     Sysvar<'info, Rent>,
 }
 
-pub fn transfer(ctx: Context<TransferTokens>, amount: u64) -> Result<()> {
-    let launch  = &ctx.accounts.launch_metadata;
-    let record  = &mut ctx.accounts.user_record;
-    let source  = &ctx.accounts.source_token_account;
-    let now     = Clock::get()?.unix_timestamp;
-
-    require!(launch.is_window_open(now), LaunchError::NotInTradingWindow);
-
-    let today = (now / 86_400) as u64;
-    require!(record.last_action_day != today, LaunchError::ActionAlreadyPerformed);
-
-    let max_send = source.amount / 5;
-    require!(amount <= max_send, LaunchError::ExceedsTransferLimit);
+/// Moves `amount` of the sender's tokens inside an open window and within
+/// their transfer limit. With the transfer hook on, the hook enforces and
+/// records the limit, so it is counted there only; the sender's hook record
+/// is opened on first use. Remaining accounts are forwarded to the hook.
+pub(crate) fn transfer<'info>(ctx: Context<'_, '_, '_, 'info, TransferTokens<'info>>, amount: u64) -> Result<()> {
+    let accounts = ctx.accounts;
+    let launch   = &accounts.launch_metadata;
+    let policy   = &mut accounts.launch_policy;
+    let record   = &mut accounts.user_record;
+    let source   = &accounts.source_token_account;
+    let from     = accounts.from.key();
+    let now      = Clock::get()?.unix_timestamp;
+
+    require!(!launch.is_paused(&accounts.global_state), LaunchError::Paused);
+    policy.roll_windows(&accounts.randomness, now)?;
+    // The hook reads the policy mid-transfer, so a fresh draw is written out
+    // before the token program runs it.
+    policy.exit(&crate::ID)?;
+    require!(policy.schedule.is_open(now), LaunchError::NotInTradingWindow);
+
+    let mint = accounts.token_mint.to_account_info();
+    let hooked = token_extensions::has_sendor_hook(&mint)?;
+    if hooked {
+        let (Some(hook_program), Some(transfer_record)) =
+            (&accounts.transfer_hook_program, &accounts.transfer_record)
+        else {
+            return err!(LaunchError::MissingHookAccounts);
+        };
+        transfer_hook::init_transfer_record(
+            hook_program.to_account_info(),
+            InitTransferRecord {
+                payer: accounts.from.to_account_info(),
+                mint: accounts.token_mint.to_account_info(),
+                transfer_record: transfer_record.to_account_info(),
+                system_program: accounts.system_program.to_account_info(),
+            },
+            from,
+        )?;
+    } else {
+        let limit = record.transfer.at(now, source.amount);
+        require!(limit.action_count() < policy.actions_per_day, LaunchError::ActionAlreadyPerformed);
+
+        let allowance = limit.remaining(policy.max_transfer(limit.snapshot_balance)?);
+        require!(amount <= allowance, LaunchError::ExceedsTransferLimit);
+    }
 
-    token::transfer(
+    transfer_hook::transfer_checked(
         CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            SplTransfer {
+            accounts.token_program.to_account_info(),
+            TransferChecked {
                 from:      source.to_account_info(),
-                to:        ctx.accounts.destination_token_account.to_account_info(),
-                authority: ctx.accounts.from.to_account_info(),
+                mint:      accounts.token_mint.to_account_info(),
+                to:        accounts.destination_token_account.to_account_info(),
+                authority: accounts.from.to_account_info(),
             },
-        ),
+        )
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec()),
         amount,
+        accounts.token_mint.decimals,
     )?;
 
-    record.last_action_day = today;
-    if record.user == Pubkey::default() {
-        record.user = ctx.accounts.from.key();
+    if !hooked {
+        record.record_transfer(from, now, source.amount, amount)?;
     }
+    let transfer_limit = transfer_hook::transfer_usage(
+        &mint,
+        accounts.transfer_record.as_ref().map(|r| r.as_ref()),
+        record.transfer,
+    )?;
 
     emit!(TransferEvent {
-        from: ctx.accounts.from.key(),
-        to:   ctx.accounts.to.key(),
+        from,
+        to:   accounts.to.key(),
         qty:  amount,
     });
+    emit!(AllowanceUpdated {
+        user:            from,
+        launch_metadata: launch.key(),
+        allowance:       policy.allowance(&record.sell, &transfer_limit, now, source.amount.saturating_sub(amount))?,
+    });
 
     Ok(())
 }
@@ -0,0 +1,310 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::associated_token;
+use anchor_spl::token_interface::{
+    self, Burn, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked,
+};
+use crate::errors::LaunchError;
+use crate::state::vesting::{VestingParams, VestingSchedule, VESTING_SEED};
+use crate::transfer_hook;
+
+#[event]
+pub struct VestingCreated {
+    pub launch_metadata: Pubkey,
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+    pub start: i64,
+    pub cliff: i64,
+    pub duration: i64,
+}
+
+#[event]
+pub struct VestedClaimed {
+    pub launch_metadata: Pubkey,
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+    pub claimed_amount: u64,
+}
+
+#[event]
+pub struct VestingRevoked {
+    pub launch_metadata: Pubkey,
+    pub beneficiary: Pubkey,
+    pub vested: u64,
+    pub burned: u64,
+}
+
+#[derive(Accounts)]
+pub struct ClaimVested<'info> {
+    #[account(
+        mut,
+        seeds = [
+            VESTING_SEED,
+            vesting_schedule.launch_metadata.as_ref(),
+            beneficiary.key().as_ref()
+        ],
+        bump = vesting_schedule.bump,
+        has_one = beneficiary,
+        has_one = token_mint,
+        has_one = token_account
+    )]
+    pub vesting_schedule: Account<'info, VestingSchedule>,
+    #[account(mut)]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mint::token_program = token_program)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub beneficiary: Signer<'info>,
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = beneficiary,
+        token::token_program = token_program
+    )]
+    pub beneficiary_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeVesting<'info> {
+    #[account(mut, has_one = authority, has_one = token_mint, has_one = token_account)]
+    pub vesting_schedule: Account<'info, VestingSchedule>,
+    #[account(mut)]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, mint::token_program = token_program)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Accounts `create_launch` opens schedules with. Each schedule and its token
+/// account come in as a pair of remaining accounts, since their number varies
+/// per launch.
+pub struct OpenVesting<'info> {
+    pub payer: AccountInfo<'info>,
+    pub launch_metadata: AccountInfo<'info>,
+    pub mint: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub associated_token_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+}
+
+/// Creates the schedule for `params` at its PDA, opens the schedule's
+/// associated token account and mints the allocation into it. `launch_seeds`
+/// sign as the mint authority.
+pub fn open_vesting<'info>(
+    accounts: &OpenVesting<'info>,
+    schedule: &AccountInfo<'info>,
+    token_account: &AccountInfo<'info>,
+    params: &VestingParams,
+    start: i64,
+    authority: Pubkey,
+    launch_seeds: &[&[u8]],
+) -> Result<()> {
+    let launch_key = accounts.launch_metadata.key();
+    let (address, bump) = Pubkey::find_program_address(
+        &[VESTING_SEED, launch_key.as_ref(), params.beneficiary.as_ref()],
+        &crate::ID,
+    );
+    require_keys_eq!(schedule.key(), address, LaunchError::MissingVestingAccounts);
+    let seeds: &[&[u8]] = &[
+        VESTING_SEED,
+        launch_key.as_ref(),
+        params.beneficiary.as_ref(),
+        &[bump],
+    ];
+    create_pda(accounts, schedule, VestingSchedule::LEN, seeds)?;
+
+    let vesting = VestingSchedule {
+        launch_metadata: launch_key,
+        beneficiary: params.beneficiary,
+        authority,
+        token_mint: accounts.mint.key(),
+        token_account: token_account.key(),
+        total_amount: params.amount,
+        claimed_amount: 0,
+        start,
+        cliff: params.cliff,
+        duration: params.duration,
+        revoked: false,
+        bump,
+    };
+    vesting.try_serialize(&mut &mut schedule.try_borrow_mut_data()?[..])?;
+
+    associated_token::create(CpiContext::new(
+        accounts.associated_token_program.clone(),
+        associated_token::Create {
+            payer: accounts.payer.clone(),
+            associated_token: token_account.clone(),
+            authority: schedule.clone(),
+            mint: accounts.mint.clone(),
+            system_program: accounts.system_program.clone(),
+            token_program: accounts.token_program.clone(),
+        },
+    ))?;
+    token_interface::mint_to(
+        CpiContext::new_with_signer(
+            accounts.token_program.clone(),
+            MintTo {
+                mint: accounts.mint.clone(),
+                to: token_account.clone(),
+                authority: accounts.launch_metadata.clone(),
+            },
+            &[launch_seeds],
+        ),
+        params.amount,
+    )?;
+
+    emit!(VestingCreated {
+        launch_metadata: launch_key,
+        beneficiary: params.beneficiary,
+        amount: params.amount,
+        start,
+        cliff: params.cliff,
+        duration: params.duration,
+    });
+    Ok(())
+}
+
+/// Allocates a program-owned PDA. Lamports already sent to the address are
+/// kept, so pre-funding it cannot block the launch.
+fn create_pda<'info>(
+    accounts: &OpenVesting<'info>,
+    info: &AccountInfo<'info>,
+    space: usize,
+    seeds: &[&[u8]],
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(space);
+    let system = accounts.system_program.clone();
+    if info.lamports() == 0 {
+        return system_program::create_account(
+            CpiContext::new_with_signer(
+                system,
+                system_program::CreateAccount {
+                    from: accounts.payer.clone(),
+                    to: info.clone(),
+                },
+                &[seeds],
+            ),
+            rent,
+            space as u64,
+            &crate::ID,
+        );
+    }
+
+    let top_up = rent.saturating_sub(info.lamports());
+    if top_up > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system.clone(),
+                system_program::Transfer {
+                    from: accounts.payer.clone(),
+                    to: info.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+    system_program::allocate(
+        CpiContext::new_with_signer(
+            system.clone(),
+            system_program::Allocate { account_to_allocate: info.clone() },
+            &[seeds],
+        ),
+        space as u64,
+    )?;
+    system_program::assign(
+        CpiContext::new_with_signer(
+            system,
+            system_program::Assign { account_to_assign: info.clone() },
+            &[seeds],
+        ),
+        &crate::ID,
+    )
+}
+
+/// Sends the beneficiary everything vested and not yet claimed. Remaining
+/// accounts are forwarded to the mint's transfer hook.
+pub(crate) fn claim_vested<'info>(ctx: Context<'_, '_, '_, 'info, ClaimVested<'info>>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let accounts = ctx.accounts;
+    let schedule = &mut accounts.vesting_schedule;
+    let amount = schedule.claimable(now)?;
+    require!(amount > 0, LaunchError::NothingToClaim);
+    schedule.claimed_amount = schedule
+        .claimed_amount
+        .checked_add(amount)
+        .ok_or(LaunchError::MathOverflow)?;
+
+    let seeds: &[&[u8]] = &[
+        VESTING_SEED,
+        schedule.launch_metadata.as_ref(),
+        schedule.beneficiary.as_ref(),
+        &[schedule.bump],
+    ];
+    transfer_hook::transfer_checked(
+        CpiContext::new_with_signer(
+            accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: accounts.token_account.to_account_info(),
+                to: accounts.beneficiary_token_account.to_account_info(),
+                authority: schedule.to_account_info(),
+                mint: accounts.token_mint.to_account_info(),
+            },
+            &[seeds],
+        )
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+        amount,
+        accounts.token_mint.decimals,
+    )?;
+
+    emit!(VestedClaimed {
+        launch_metadata: schedule.launch_metadata,
+        beneficiary: schedule.beneficiary,
+        amount,
+        claimed_amount: schedule.claimed_amount,
+    });
+    Ok(())
+}
+
+/// Ends the schedule at what has vested so far and burns the rest. What had
+/// vested stays claimable by the beneficiary.
+pub(crate) fn revoke_vesting(ctx: Context<RevokeVesting>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let accounts = ctx.accounts;
+    let schedule = &mut accounts.vesting_schedule;
+    require!(!schedule.revoked, LaunchError::AlreadyRevoked);
+
+    let vested = schedule.vested_at(now)?;
+    let burned = schedule.total_amount - vested;
+    schedule.total_amount = vested;
+    schedule.revoked = true;
+
+    if burned > 0 {
+        let seeds: &[&[u8]] = &[
+            VESTING_SEED,
+            schedule.launch_metadata.as_ref(),
+            schedule.beneficiary.as_ref(),
+            &[schedule.bump],
+        ];
+        token_interface::burn(
+            CpiContext::new_with_signer(
+                accounts.token_program.to_account_info(),
+                Burn {
+                    mint: accounts.token_mint.to_account_info(),
+                    from: accounts.token_account.to_account_info(),
+                    authority: schedule.to_account_info(),
+                },
+                &[seeds],
+            ),
+            burned,
+        )?;
+    }
+
+    emit!(VestingRevoked {
+        launch_metadata: schedule.launch_metadata,
+        beneficiary: schedule.beneficiary,
+        vested,
+        burned,
+    });
+    Ok(())
+}
@@ -3,56 +3,234 @@ use anchor_lang::prelude::*;
 declare_id!("6mqsEaGREVXfAroU9WErmEPqYmKoFpoMHuFHzvBBGgna");
 
 pub mod constants;
+pub mod curve;
 pub mod errors;
 pub mod state;
 pub mod instructions;
+pub mod transfer_hook;
+pub mod token_extensions;
 
-/* re-export every item (struct + constant) from each instruction module */
+/* re-export the accounts, events and helpers of each instruction module;
+   the handlers stay crate-private so they cannot clash with the program fns */
 pub use instructions::initialize::*;
 pub use instructions::create_launch::*;
 pub use instructions::buy::*;
 pub use instructions::sell::*;
 pub use instructions::transfer::*;
-pub use instructions::update_global::*;
 pub use instructions::set_sell_window::*;
 pub use instructions::migrate::*;
+pub use instructions::set_trade_fees::*;
+pub use instructions::creator_controls::*;
+pub use instructions::admin_controls::*;
+pub use instructions::randomness::*;
+pub use instructions::allowance::*;
+pub use instructions::realloc_launch::*;
+pub use instructions::vesting::*;
+pub use state::bonding_curve_state::SupplyParams;
+pub use state::curve_kind::{CurveKind, PricePoint};
+pub use state::graduation::GraduationThreshold;
+pub use state::launch_policy::{Allowance, PolicyBounds, PolicyParams};
+pub use state::trade_fees::TradeFees;
+pub use state::vesting::VestingParams;
+pub use token_extensions::{TokenExtensions, TransferFeeParams};
 
 #[program]
 pub mod sendor {
     use super::*;
     use crate::instructions::{
-        buy, create_launch, initialize, migrate, sell, set_sell_window, transfer, update_global,
+        admin_controls, allowance, buy, create_launch, creator_controls, initialize, migrate,
+        randomness, realloc_launch, sell, set_sell_window, set_trade_fees, transfer, vesting,
     };
 
     pub fn initialize(ctx: Context<Initialize>, platform_fee_recipient: Pubkey, launch_fee_lamports: u64) -> Result<()> {
         initialize::initialize(ctx, platform_fee_recipient, launch_fee_lamports)
     }
 
-    pub fn create_launch(ctx: Context<CreateLaunch>, base_price: u64, slope: u64, token_name: String, token_symbol: String, token_uri: String) -> Result<()> {
-        create_launch::create_launch(ctx, base_price, slope, token_name, token_symbol, token_uri)
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_launch<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateLaunch<'info>>,
+        curve: CurveKind,
+        supply: SupplyParams,
+        graduation: GraduationThreshold,
+        allocations: Vec<VestingParams>,
+        policy: PolicyParams,
+        extensions: TokenExtensions,
+        token_name: String,
+        token_symbol: String,
+        token_uri: String,
+    ) -> Result<()> {
+        create_launch::create_launch(
+            ctx,
+            curve,
+            supply,
+            graduation,
+            allocations,
+            policy,
+            extensions,
+            token_name,
+            token_symbol,
+            token_uri,
+        )
     }
 
-    pub fn buy(ctx: Context<Buy>, amount: u64, max_cost: u64) -> Result<()> {
+    pub fn buy<'info>(
+        ctx: Context<'_, '_, '_, 'info, Buy<'info>>,
+        amount: u64,
+        max_cost: u64,
+    ) -> Result<()> {
         buy::buy(ctx, amount, max_cost)
     }
 
-    pub fn sell(ctx: Context<Sell>, amount: u64, min_payout: u64) -> Result<()> {
+    pub fn buy_exact_in<'info>(
+        ctx: Context<'_, '_, '_, 'info, Buy<'info>>,
+        lamports_in: u64,
+        min_tokens_out: u64,
+    ) -> Result<u64> {
+        buy::buy_exact_in(ctx, lamports_in, min_tokens_out)
+    }
+
+    pub fn sell<'info>(
+        ctx: Context<'_, '_, '_, 'info, Sell<'info>>,
+        amount: u64,
+        min_payout: u64,
+    ) -> Result<()> {
         sell::sell(ctx, amount, min_payout)
     }
 
-    pub fn transfer(ctx: Context<TransferTokens>, amount: u64) -> Result<()> {
-        transfer::transfer(ctx, amount)
+    pub fn sell_exact_out<'info>(
+        ctx: Context<'_, '_, '_, 'info, Sell<'info>>,
+        lamports_out: u64,
+        max_tokens_in: u64,
+    ) -> Result<u64> {
+        sell::sell_exact_out(ctx, lamports_out, max_tokens_in)
+    }
+
+    pub fn emergency_sell<'info>(
+        ctx: Context<'_, '_, '_, 'info, Sell<'info>>,
+        amount: u64,
+        min_payout: u64,
+    ) -> Result<()> {
+        sell::emergency_sell(ctx, amount, min_payout)
+    }
+
+    pub fn get_allowance(ctx: Context<GetAllowance>) -> Result<Allowance> {
+        allowance::get_allowance(ctx)
     }
 
-    pub fn update_global(ctx: Context<UpdateGlobal>) -> Result<()> {
-        update_global::update_global(ctx)
+    pub fn migrate_user_record(ctx: Context<MigrateUserRecord>) -> Result<()> {
+        allowance::migrate_user_record(ctx)
+    }
+
+    pub fn realloc_launch(ctx: Context<ReallocLaunch>, launch_id: u64) -> Result<()> {
+        realloc_launch::realloc_launch(ctx, launch_id)
+    }
+
+    pub fn transfer<'info>(
+        ctx: Context<'_, '_, '_, 'info, TransferTokens<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        transfer::transfer(ctx, amount)
     }
 
     pub fn randomize_sell_window(ctx: Context<RandomizeSellWindow>) -> Result<()> {
         set_sell_window::randomize_sell_window(ctx)
     }
 
-    pub fn migrate(ctx: Context<Migrate>) -> Result<()> {
+    /// Former name of `randomize_sell_window`, kept for existing clients.
+    pub fn update_global(ctx: Context<RandomizeSellWindow>) -> Result<()> {
+        set_sell_window::randomize_sell_window(ctx)
+    }
+
+    pub fn set_window_schedule(
+        ctx: Context<SetWindowSchedule>,
+        count: u8,
+        duration: i64,
+        min_gap: i64,
+    ) -> Result<()> {
+        set_sell_window::set_window_schedule(ctx, count, duration, min_gap)
+    }
+
+    pub fn propose_admin(ctx: Context<AdminOnly>, proposed: Pubkey) -> Result<()> {
+        admin_controls::propose_admin(ctx, proposed)
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        admin_controls::accept_admin(ctx)
+    }
+
+    pub fn set_fee_recipient(ctx: Context<AdminOnly>, new_recipient: Pubkey) -> Result<()> {
+        admin_controls::set_fee_recipient(ctx, new_recipient)
+    }
+
+    pub fn set_launch_fee(ctx: Context<AdminOnly>, new_fee_lamports: u64) -> Result<()> {
+        admin_controls::set_launch_fee(ctx, new_fee_lamports)
+    }
+
+    pub fn set_global_pause(ctx: Context<AdminOnly>, paused: bool) -> Result<()> {
+        admin_controls::set_global_pause(ctx, paused)
+    }
+
+    pub fn set_launch_pause(ctx: Context<AdminLaunchOnly>, paused: bool) -> Result<()> {
+        admin_controls::set_launch_pause(ctx, paused)
+    }
+
+    pub fn set_policy_bounds(ctx: Context<AdminOnly>, bounds: PolicyBounds) -> Result<()> {
+        admin_controls::set_policy_bounds(ctx, bounds)
+    }
+
+    pub fn realloc_global(ctx: Context<ReallocGlobal>) -> Result<()> {
+        admin_controls::realloc_global(ctx)
+    }
+
+    pub fn set_trade_fees(ctx: Context<SetTradeFees>, fees: TradeFees) -> Result<()> {
+        set_trade_fees::set_trade_fees(ctx, fees)
+    }
+
+    pub fn set_launch_fees(ctx: Context<SetLaunchFees>, fee_override: Option<TradeFees>) -> Result<()> {
+        set_trade_fees::set_launch_fees(ctx, fee_override)
+    }
+
+    pub fn update_token_uri(ctx: Context<UpdateTokenUri>, token_uri: String) -> Result<()> {
+        creator_controls::update_token_uri(ctx, token_uri)
+    }
+
+    pub fn set_creator_pause(ctx: Context<CreatorOnly>, paused: bool) -> Result<()> {
+        creator_controls::set_creator_pause(ctx, paused)
+    }
+
+    pub fn transfer_creator(ctx: Context<CreatorOnly>, new_creator: Pubkey) -> Result<()> {
+        creator_controls::transfer_creator(ctx, new_creator)
+    }
+
+    pub fn claim_creator_fees(ctx: Context<ClaimCreatorFees>) -> Result<()> {
+        creator_controls::claim_creator_fees(ctx)
+    }
+
+    pub fn init_randomness_oracle(ctx: Context<InitRandomnessOracle>, authority: Pubkey) -> Result<()> {
+        randomness::init_randomness_oracle(ctx, authority)
+    }
+
+    pub fn set_randomness_authority(ctx: Context<SetRandomnessAuthority>, authority: Pubkey) -> Result<()> {
+        randomness::set_randomness_authority(ctx, authority)
+    }
+
+    pub fn commit_randomness(ctx: Context<OracleRound>, commitment: [u8; 32]) -> Result<()> {
+        randomness::commit_randomness(ctx, commitment)
+    }
+
+    pub fn reveal_randomness(ctx: Context<RevealRandomness>, secret: [u8; 32]) -> Result<()> {
+        randomness::reveal_randomness(ctx, secret)
+    }
+
+    pub fn migrate<'info>(ctx: Context<'_, '_, '_, 'info, Migrate<'info>>) -> Result<()> {
         migrate::migrate(ctx)
     }
+
+    pub fn claim_vested<'info>(ctx: Context<'_, '_, '_, 'info, ClaimVested<'info>>) -> Result<()> {
+        vesting::claim_vested(ctx)
+    }
+
+    pub fn revoke_vesting(ctx: Context<RevokeVesting>) -> Result<()> {
+        vesting::revoke_vesting(ctx)
+    }
 }
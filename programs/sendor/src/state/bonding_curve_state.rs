@@ -1,27 +1,287 @@
 use anchor_lang::prelude::*;
+use crate::constants::BPS_DENOMINATOR;
+use crate::curve::PricingCurve;
 use crate::errors::LaunchError;
+use crate::state::{curve_kind::CurveKind, graduation::GraduationThreshold, trade_fees::TradeFees};
+
+/// Supply a creator picks at `create_launch`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SupplyParams {
+    /// Minted in total, in base units
+    pub total_supply: u64,
+    pub decimals: u8,
+    /// Share of the total sold off the curve. The rest is held in vesting
+    /// schedules or seeds the pool at graduation.
+    pub sale_bps: u16,
+}
+
+impl SupplyParams {
+    /// Validates the parameters and returns how many base units the curve
+    /// may sell.
+    pub fn sale_supply(&self) -> Result<u64> {
+        require!(self.decimals <= 18, LaunchError::InvalidDecimals);
+        require!(
+            self.sale_bps > 0 && self.sale_bps as u64 <= BPS_DENOMINATOR,
+            LaunchError::InvalidParams
+        );
+        let sale = self.total_supply as u128 * self.sale_bps as u128 / BPS_DENOMINATOR as u128;
+        require!(sale > 0, LaunchError::InvalidParams);
+        Ok(sale as u64)
+    }
+}
 
 #[account]
+#[derive(InitSpace)]
 pub struct BondingCurveState {
     pub launch_metadata: Pubkey,
-    pub base_price: u64,
-    pub slope: u64,
+    pub curve: CurveKind,
     pub current_supply: u64,
     pub decimals: u8,
+    /// Minted at launch, in base units
+    pub total_supply: u64,
+    /// Most the curve will ever have sold; `buy` stops here
+    pub sale_supply: u64,
+    /// Lamports backing the tokens sold off the curve, held in the launch's
+    /// `sol_vault`. Fees and rent are kept out, so this is exactly what
+    /// graduates into the pool.
+    pub real_sol_reserves: u64,
+    /// Minted into vesting schedules at launch and never sold off the curve
+    pub vested_supply: u64,
+    /// Point at which the launch may migrate to its AMM pool
+    pub graduation: GraduationThreshold,
 }
 
 impl BondingCurveState {
-    pub const LEN: usize = 8 + 32 + 8 + 8 + 8 + 1;
+    /// 8-byte discriminator + fields
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
 
-    pub fn compute_payout(&self, amount: u64) -> Result<u64> {
-        require!(self.decimals <= 18, LaunchError::InvalidDecimals);
-        let m = 10u128.pow(self.decimals as u32);
-        let base = self.base_price as u128;
-        let slope = self.slope as u128;
-        let s_end = self.current_supply as u128;
-        let _s_start = s_end.checked_sub(amount as u128).ok_or(LaunchError::MathOverflow)?;
-        let payout = base * amount as u128
-            + slope * (s_end * amount as u128 - amount as u128 * (amount as u128 - 1) / 2);
-        Ok((payout / m) as u64)
+    /// Size of curves written by the original layout: discriminator + launch
+    /// + base price + slope + current supply + decimals.
+    pub const LEGACY_LEN: usize = 8 + 32 + 8 + 8 + 8 + 1;
+
+    /// Decodes a curve written under the original layout. Its slope was per
+    /// base unit where `CurveKind::Linear` prices per whole token, so it is
+    /// scaled by `10^decimals` to keep every price where it was. Everything
+    /// minted was for sale and there was no threshold, so the launch
+    /// graduates once sold out.
+    pub fn from_legacy(data: &[u8], total_supply: u64, real_sol_reserves: u64) -> Result<Self> {
+        require!(data.len() == Self::LEGACY_LEN, LaunchError::InvalidParams);
+        require!(data[..8] == *Self::DISCRIMINATOR, LaunchError::InvalidParams);
+        let word = |at: usize| u64::from_le_bytes(data[at..at + 8].try_into().unwrap());
+        let decimals = data[64];
+        require!(decimals <= 18, LaunchError::InvalidDecimals);
+        let slope = word(48)
+            .checked_mul(10u64.pow(decimals as u32))
+            .ok_or(LaunchError::MathOverflow)?;
+        Ok(Self {
+            launch_metadata: Pubkey::try_from(&data[8..40]).unwrap(),
+            curve: CurveKind::Linear { base_price: word(40), slope },
+            current_supply: word(56),
+            decimals,
+            total_supply,
+            sale_supply: total_supply,
+            real_sol_reserves,
+            vested_supply: 0,
+            graduation: GraduationThreshold::SupplySold(total_supply),
+        })
+    }
+
+    /// The SOL vault must hold the recorded reserve on top of its own rent.
+    /// Checked after every trade so accounting drift fails the transaction.
+    pub fn check_reserves(&self, sol_vault: &AccountInfo) -> Result<()> {
+        let rent = Rent::get()?.minimum_balance(sol_vault.data_len());
+        let required = self
+            .real_sol_reserves
+            .checked_add(rent)
+            .ok_or(LaunchError::MathOverflow)?;
+        require!(sol_vault.lamports() >= required, LaunchError::ReserveMismatch);
+        Ok(())
+    }
+
+    /// Takes `lamports` out of the reserve ahead of paying them from a vault
+    /// holding `vault_lamports`. The vault's rent-exempt minimum is never
+    /// withdrawable, so the last sell fails cleanly instead of leaving the
+    /// vault for the runtime to reclaim.
+    pub fn release(&mut self, lamports: u64, vault_lamports: u64, vault_rent: u64) -> Result<()> {
+        let reserves = self
+            .real_sol_reserves
+            .checked_sub(lamports)
+            .ok_or(LaunchError::InsufficientLiquidity)?;
+        require!(
+            lamports <= vault_lamports.saturating_sub(vault_rent),
+            LaunchError::BelowRentExemption
+        );
+        self.real_sol_reserves = reserves;
+        Ok(())
+    }
+
+    /// Tokens the curve can still sell.
+    pub fn available_supply(&self) -> Result<u64> {
+        self.sale_supply
+            .checked_sub(self.current_supply)
+            .ok_or(error!(LaunchError::InsufficientSupply))
+    }
+
+    pub fn cost_to_buy(&self, amount: u64) -> Result<u64> {
+        self.curve.cost_to_buy(self.current_supply, amount, self.decimals)
+    }
+
+    pub fn payout_to_sell(&self, amount: u64) -> Result<u64> {
+        self.curve.payout_to_sell(self.current_supply, amount, self.decimals)
+    }
+
+    pub fn max_buy_for(&self, lamports: u64, cap: u64) -> Result<u64> {
+        self.curve.max_buy_for(self.current_supply, lamports, cap, self.decimals)
+    }
+
+    pub fn min_sell_for(&self, lamports: u64) -> Result<u64> {
+        self.curve.min_sell_for(self.current_supply, lamports, self.decimals)
+    }
+
+    /// Fewest tokens whose payout, after `fees`, still reaches `net`. Fees
+    /// floor per recipient, so a larger payout can net a lamport less; the
+    /// target is raised until the payout the curve actually makes covers it.
+    pub fn min_sell_for_net(&self, fees: &TradeFees, net: u64) -> Result<u64> {
+        let mut target = fees.min_principal(net)?;
+        loop {
+            let amount = self.min_sell_for(target)?;
+            let gross = self.payout_to_sell(amount)?;
+            if gross - fees.charge(gross).total() >= net {
+                return Ok(amount);
+            }
+            target = gross.checked_add(1).ok_or(LaunchError::MathOverflow)?;
+        }
+    }
+
+    pub fn spot_price(&self) -> Result<u64> {
+        self.curve.spot_price(self.current_supply, self.decimals)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{INITIAL_SUPPLY_BASE_UNITS, TOKEN_DECIMALS};
+    use proptest::prelude::*;
+
+    /// Rent-exempt minimum of a zero-data account
+    const VAULT_RENT: u64 = 890_880;
+
+    fn linear(base_price: u64, slope: u64) -> BondingCurveState {
+        BondingCurveState {
+            launch_metadata: Pubkey::default(),
+            curve: CurveKind::Linear { base_price, slope },
+            current_supply: 0,
+            decimals: TOKEN_DECIMALS,
+            total_supply: INITIAL_SUPPLY_BASE_UNITS,
+            sale_supply: INITIAL_SUPPLY_BASE_UNITS,
+            graduation: GraduationThreshold::SupplySold(INITIAL_SUPPLY_BASE_UNITS),
+            real_sol_reserves: 0,
+            vested_supply: 0,
+        }
+    }
+
+    #[test]
+    fn legacy_curves_keep_their_prices() {
+        let launch = Pubkey::new_unique();
+        let mut data = BondingCurveState::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(launch.as_ref());
+        for word in [1_000u64, 3, 2_000_000_000] {
+            data.extend_from_slice(&word.to_le_bytes());
+        }
+        data.push(TOKEN_DECIMALS);
+
+        let curve = BondingCurveState::from_legacy(&data, INITIAL_SUPPLY_BASE_UNITS, 5).unwrap();
+        assert_eq!(curve.launch_metadata, launch);
+        assert_eq!(curve.real_sol_reserves, 5);
+        // The original price was base + slope * supply in base units.
+        assert_eq!(curve.spot_price().unwrap(), 1_000 + 3 * 2_000_000_000);
+        assert!(BondingCurveState::from_legacy(&data[1..], INITIAL_SUPPLY_BASE_UNITS, 5).is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn final_tokens_sell_against_the_reserve(
+            base_price in 1..=1_000_000u64,
+            slope in 1..=10u64,
+            buys in prop::collection::vec(1..INITIAL_SUPPLY_BASE_UNITS / 8, 1..8),
+        ) {
+            let mut curve = linear(base_price, slope);
+            let mut vault = VAULT_RENT;
+            for &amount in &buys {
+                let cost = curve.cost_to_buy(amount)?;
+                curve.current_supply += amount;
+                curve.real_sol_reserves += cost;
+                vault += cost;
+            }
+            // Every holder exits, the last sell taking the curve back to zero.
+            for &amount in buys.iter().rev() {
+                let payout = curve.payout_to_sell(amount)?;
+                curve.release(payout, vault, VAULT_RENT)?;
+                curve.current_supply -= amount;
+                vault -= payout;
+            }
+            prop_assert_eq!(curve.current_supply, 0);
+            prop_assert_eq!(vault, VAULT_RENT + curve.real_sol_reserves);
+        }
+
+        #[test]
+        fn exact_out_sells_pay_at_least_the_target(
+            base_price in 1..=1_000_000u64,
+            slope in 1..=10u64,
+            protocol_bps in 0..=500u16,
+            creator_bps in 0..=500u16,
+            sold in 1..INITIAL_SUPPLY_BASE_UNITS,
+            net in 1..=1_000_000u64,
+        ) {
+            let mut curve = linear(base_price, slope);
+            curve.current_supply = sold;
+            let fees = TradeFees { protocol_bps, creator_bps };
+            let Ok(amount) = curve.min_sell_for_net(&fees, net) else {
+                return Ok(());
+            };
+            let gross = curve.payout_to_sell(amount)?;
+            let split = fees.charge(gross);
+            let payout = gross - split.total();
+            // `lamports_out` is a floor, not an exact amount.
+            prop_assert!(payout >= net, "paid {} for a target of {}", payout, net);
+            prop_assert!(payout + split.total() <= gross);
+        }
+    }
+
+    #[test]
+    fn sale_supply_is_the_chosen_share() {
+        let supply = SupplyParams { total_supply: 1_000_000, decimals: 6, sale_bps: 8_000 };
+        assert_eq!(supply.sale_supply().unwrap(), 800_000);
+        assert_eq!(SupplyParams { sale_bps: 10_000, ..supply }.sale_supply().unwrap(), 1_000_000);
+
+        for bad in [
+            SupplyParams { sale_bps: 0, ..supply },
+            SupplyParams { sale_bps: 10_001, ..supply },
+            SupplyParams { total_supply: 1, ..supply },
+            SupplyParams { decimals: 19, ..supply },
+        ] {
+            assert!(bad.sale_supply().is_err(), "{bad:?}");
+        }
+    }
+
+    #[test]
+    fn release_never_touches_the_vault_rent() {
+        let mut curve = linear(1_000, 1);
+        curve.real_sol_reserves = 1_000;
+
+        // A vault one lamport short of backing the reserve above its rent
+        assert_eq!(
+            curve.release(1_000, VAULT_RENT + 999, VAULT_RENT).unwrap_err(),
+            LaunchError::BelowRentExemption.into()
+        );
+        assert_eq!(
+            curve.release(1_001, VAULT_RENT + 1_001, VAULT_RENT).unwrap_err(),
+            LaunchError::InsufficientLiquidity.into()
+        );
+        assert_eq!(curve.real_sol_reserves, 1_000);
+
+        curve.release(1_000, VAULT_RENT + 1_000, VAULT_RENT).unwrap();
+        assert_eq!(curve.real_sol_reserves, 0);
     }
 }
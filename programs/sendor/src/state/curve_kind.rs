@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+/// Maximum number of breakpoints a piecewise-linear curve may carry.
+pub const MAX_CURVE_POINTS: usize = 8;
+
+/// A single breakpoint on a piecewise-linear curve.
+/// `supply` is in token base units, `price` in lamports per whole token.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct PricePoint {
+    pub supply: u64,
+    pub price: u64,
+}
+
+/// Shape of the bonding curve, chosen by the creator at `create_launch`.
+/// All prices are lamports per whole token; supplies are token base units.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CurveKind {
+    /// price = base_price + slope * supply_in_whole_tokens
+    Linear { base_price: u64, slope: u64 },
+    /// price = base_price * (1 + growth_bps / 10_000) ^ (supply / step)
+    Exponential { base_price: u64, growth_bps: u16, step: u64 },
+    /// pump-style x * y = k over virtual SOL and token reserves
+    ConstantProduct { virtual_sol_reserves: u64, virtual_token_reserves: u64 },
+    /// straight segments between `points[..len]`, flat after the last point
+    PiecewiseLinear { points: [PricePoint; MAX_CURVE_POINTS], len: u8 },
+}
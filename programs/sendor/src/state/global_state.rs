@@ -1,10 +1,13 @@
 use anchor_lang::prelude::*;
+use crate::state::{launch_policy::PolicyBounds, trade_fees::TradeFees};
 
 #[account]
-#[derive(Default)]
+#[derive(Default, InitSpace)]
 pub struct GlobalState {
     /// Administrator of the launchpad (initial signer)
     pub admin:        Pubkey,
+    /// Proposed successor; must sign `accept_admin` before taking over
+    pub pending_admin: Pubkey,
     /// Wallet to receive platform fees for token launches
     pub platform_fee_recipient: Pubkey,
     /// Fee amount in lamports for creating a new launch
@@ -12,6 +15,14 @@ pub struct GlobalState {
     /// Counts how many token launches have been created; each `create_launch`
     /// increments this and uses the value as a seed.
     pub launch_count: u64,
+    /// Default buy/sell fees; a launch may carry its own override
+    pub trade_fees: TradeFees,
+    /// Emergency switch halting every launch at once
+    pub paused: bool,
+    /// When `paused` was last switched on (0 while trading)
+    pub paused_at: i64,
+    /// Limits on the trading rules creators may pick for their launches
+    pub policy_bounds: PolicyBounds,
     /// PDA bump for `global_state` (handy for future CPI calls)
     pub bump:         u8,
     /// Padding to ensure proper alignment
@@ -19,6 +30,11 @@ pub struct GlobalState {
 }
 
 impl GlobalState {
-    /// Account size: 8-byte Anchor discriminator + 32 (admin) + 32 (fee_recipient) + 8 (fee_lamports) + 8 (count) + 1 (bump) + 7 (padding)
-    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 1 + 7;
+    /// Account size: 8-byte Anchor discriminator + fields
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+
+    /// Size of the account written before the admin handover, trade fees,
+    /// pause switch and policy bounds: discriminator + admin + fee recipient +
+    /// launch fee + launch count + bump + padding.
+    pub const LEGACY_LEN: usize = 8 + 32 + 32 + 8 + 8 + 1 + 7;
 }
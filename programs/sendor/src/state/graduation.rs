@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+use crate::errors::LaunchError;
+
+/// When a launch may graduate into its AMM pool, chosen by the creator at
+/// `create_launch`. Once met, anyone may call `migrate`.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GraduationThreshold {
+    /// Tokens sold off the curve, in base units
+    SupplySold(u64),
+    /// SOL held in the curve's reserve, in lamports
+    SolRaised(u64),
+}
+
+impl GraduationThreshold {
+    /// Rejects thresholds the curve can never reach: more than `max_supply`
+    /// sold, or more than `max_raise`, the reserve once all of it is sold.
+    pub fn validate(&self, max_supply: u64, max_raise: u64) -> Result<()> {
+        let valid = match *self {
+            Self::SupplySold(amount) => (1..=max_supply).contains(&amount),
+            Self::SolRaised(lamports) => (1..=max_raise).contains(&lamports),
+        };
+        require!(valid, LaunchError::InvalidParams);
+        Ok(())
+    }
+
+    pub fn is_met(&self, supply_sold: u64, sol_raised: u64) -> bool {
+        match *self {
+            Self::SupplySold(amount) => supply_sold >= amount,
+            Self::SolRaised(lamports) => sol_raised >= lamports,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thresholds_must_be_reachable() {
+        assert!(GraduationThreshold::SolRaised(500).validate(1_000, 500).is_ok());
+        assert!(GraduationThreshold::SolRaised(501).validate(1_000, 500).is_err());
+        assert!(GraduationThreshold::SolRaised(0).validate(1_000, 500).is_err());
+        assert!(GraduationThreshold::SupplySold(1_001).validate(1_000, 500).is_err());
+    }
+}
@@ -1,27 +1,172 @@
 use anchor_lang::prelude::*;
+use crate::constants::{MAX_TOKEN_NAME_LEN, MAX_TOKEN_SYMBOL_LEN, MAX_TOKEN_URI_LEN};
+use crate::errors::LaunchError;
+use crate::state::{global_state::GlobalState, trade_fees::TradeFees};
 
 #[account]
+#[derive(InitSpace)]
 pub struct LaunchMetadata {
+    /// Wallet that created the launch; gates the creator-only instructions
+    pub creator: Pubkey,
     pub token_mint: Pubkey,
     pub vault: Pubkey,
     pub launch_id: u64,
-    pub current_day: u64,
-    pub window1_start: i64,
-    pub window2_start: i64,
     pub bump: u8,
+    /// Bump of the system-owned `sol_vault` PDA holding the curve's reserve
+    pub sol_vault_bump: u8,
+    /// Admin-set fees replacing `GlobalState.trade_fees` for this launch
+    pub fee_override: Option<TradeFees>,
+    /// Creator's share of trading fees, held in this account until claimed
+    pub creator_fees_accrued: u64,
+    /// Set by the admin to halt trading on this launch
+    pub paused: bool,
+    /// Set by the creator to halt trading on their own launch
+    pub creator_paused: bool,
+    /// When the launch last went from trading to paused (0 while trading)
+    pub paused_at: i64,
     // Token metadata fields
-    pub token_name: String,    // Max 32 bytes
-    pub token_symbol: String,  // Max 10 bytes
-    pub token_uri: String,     // Max 200 bytes
+    #[max_len(MAX_TOKEN_NAME_LEN)]
+    pub token_name: String,
+    #[max_len(MAX_TOKEN_SYMBOL_LEN)]
+    pub token_symbol: String,
+    #[max_len(MAX_TOKEN_URI_LEN)]
+    pub token_uri: String,
 }
 
 impl LaunchMetadata {
-    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 8 + 1 + 32 + 10 + 200;
+    /// 8-byte discriminator + fields, strings at their maximum length
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
 
-    pub fn is_window_open(&self, now: i64) -> bool {
-        const LEN: i64 = 900;
-        let t = now % 86_400;
-        (t >= self.window1_start && t < self.window1_start + LEN)
-            || (t >= self.window2_start && t < self.window2_start + LEN)
+    /// Size launches were allocated at under the original layout. That `LEN`
+    /// left out one of its four 8-byte fields and the string length prefixes,
+    /// so it is kept verbatim rather than summed from the fields.
+    pub const LEGACY_LEN: usize = 8 + 32 + 32 + 8 + 8 + 8 + 1 + 32 + 10 + 200;
+
+    /// Decodes a launch written under the original layout. It recorded no
+    /// creator, so the caller supplies one; the day and window starts it kept
+    /// are superseded by the launch policy.
+    pub fn from_legacy(data: &[u8], creator: Pubkey, sol_vault_bump: u8) -> Result<Self> {
+        require!(data.len() == Self::LEGACY_LEN, LaunchError::InvalidParams);
+        require!(data[..8] == *Self::DISCRIMINATOR, LaunchError::InvalidParams);
+        let mut strings = &data[105..];
+        Ok(Self {
+            creator,
+            token_mint: Pubkey::try_from(&data[8..40]).unwrap(),
+            vault: Pubkey::try_from(&data[40..72]).unwrap(),
+            launch_id: u64::from_le_bytes(data[72..80].try_into().unwrap()),
+            bump: data[104],
+            sol_vault_bump,
+            fee_override: None,
+            creator_fees_accrued: 0,
+            paused: false,
+            creator_paused: false,
+            paused_at: 0,
+            token_name: String::deserialize(&mut strings)?,
+            token_symbol: String::deserialize(&mut strings)?,
+            token_uri: String::deserialize(&mut strings)?,
+        })
+    }
+
+    /// Fees that apply to trades on this launch.
+    pub fn trade_fees(&self, global: &TradeFees) -> TradeFees {
+        self.fee_override.unwrap_or(*global)
+    }
+
+    /// Clears the accrued creator fees and returns them for payout from this
+    /// account, which holds `lamports` and must keep `rent` of them.
+    pub fn take_creator_fees(&mut self, lamports: u64, rent: u64) -> Result<u64> {
+        let amount = self.creator_fees_accrued;
+        require!(
+            amount <= lamports.saturating_sub(rent),
+            LaunchError::BelowRentExemption
+        );
+        self.creator_fees_accrued = 0;
+        Ok(amount)
+    }
+
+    /// Paused by the admin or the creator, ignoring the global switch.
+    pub fn is_locally_paused(&self) -> bool {
+        self.paused || self.creator_paused
+    }
+
+    pub fn is_paused(&self, global: &GlobalState) -> bool {
+        global.paused || self.is_locally_paused()
+    }
+
+    /// Start of the pause currently in force, if any. When both the global
+    /// switch and the launch are paused, the older pause counts.
+    pub fn paused_since(&self, global: &GlobalState) -> Option<i64> {
+        let global_since = global.paused.then_some(global.paused_at);
+        let local_since = self.is_locally_paused().then_some(self.paused_at);
+        match (global_since, local_since) {
+            (Some(g), Some(l)) => Some(g.min(l)),
+            (g, l) => g.or(l),
+        }
+    }
+
+    /// Updates the admin and/or creator flag and keeps `paused_at` in step.
+    pub fn set_pause(&mut self, admin: Option<bool>, creator: Option<bool>, now: i64) {
+        let was_paused = self.is_locally_paused();
+        self.paused = admin.unwrap_or(self.paused);
+        self.creator_paused = creator.unwrap_or(self.creator_paused);
+        match (was_paused, self.is_locally_paused()) {
+            (false, true) => self.paused_at = now,
+            (true, false) => self.paused_at = 0,
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_launches_keep_their_identity_and_names() {
+        let (mint, vault, creator) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let mut data = vec![0u8; LaunchMetadata::LEGACY_LEN];
+        data[..8].copy_from_slice(LaunchMetadata::DISCRIMINATOR);
+        data[8..40].copy_from_slice(mint.as_ref());
+        data[40..72].copy_from_slice(vault.as_ref());
+        data[72..80].copy_from_slice(&7u64.to_le_bytes());
+        data[104] = 254;
+        let mut strings = &mut data[105..];
+        for field in ["Token", "TKN", "https://example.com/t.json"] {
+            field.to_string().serialize(&mut strings).unwrap();
+        }
+
+        let launch = LaunchMetadata::from_legacy(&data, creator, 253).unwrap();
+        assert_eq!((launch.token_mint, launch.vault, launch.creator), (mint, vault, creator));
+        assert_eq!((launch.launch_id, launch.bump, launch.sol_vault_bump), (7, 254, 253));
+        assert_eq!(launch.token_symbol, "TKN");
+        assert_eq!(launch.token_uri, "https://example.com/t.json");
+    }
+
+    #[test]
+    fn max_length_fields_fill_the_account_exactly() {
+        let launch = LaunchMetadata {
+            creator: Pubkey::new_unique(),
+            token_mint: Pubkey::new_unique(),
+            vault: Pubkey::new_unique(),
+            launch_id: u64::MAX,
+            bump: 255,
+            sol_vault_bump: 255,
+            fee_override: Some(TradeFees { protocol_bps: 100, creator_bps: 100 }),
+            creator_fees_accrued: u64::MAX,
+            paused: true,
+            creator_paused: true,
+            paused_at: i64::MAX,
+            token_name: "n".repeat(MAX_TOKEN_NAME_LEN),
+            token_symbol: "s".repeat(MAX_TOKEN_SYMBOL_LEN),
+            token_uri: "u".repeat(MAX_TOKEN_URI_LEN),
+        };
+
+        let mut data = vec![0u8; LaunchMetadata::LEN];
+        let mut writer = data.as_mut_slice();
+        launch.try_serialize(&mut writer).unwrap();
+        assert!(writer.is_empty(), "{} bytes left unused", writer.len());
+
+        let decoded = LaunchMetadata::try_deserialize(&mut data.as_slice()).unwrap();
+        assert_eq!(decoded.token_uri, launch.token_uri);
     }
 }
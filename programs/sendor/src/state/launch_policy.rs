@@ -0,0 +1,210 @@
+use anchor_lang::prelude::*;
+use crate::constants::{SELL_LIMIT_PERCENT, TRANSFER_LIMIT_PERCENT, WINDOW_DURATION};
+use crate::errors::LaunchError;
+use crate::state::{
+    randomness_oracle::RandomnessOracle,
+    user_record::RollingLimit,
+    window_schedule::{day_of, WindowSchedule, MAX_WINDOWS_PER_DAY},
+};
+
+/// Trading rules a creator picks at `create_launch`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PolicyParams {
+    /// Share of a holder's snapshot balance that may be sold per trailing 24h
+    pub sell_limit_percent: u8,
+    /// Share of a holder's snapshot balance that may be transferred per trailing 24h
+    pub transfer_limit_percent: u8,
+    /// Sells a holder may make per 24h, and separately transfers
+    pub actions_per_day: u8,
+    pub windows_per_day: u8,
+    /// Length of each trading window in seconds
+    pub window_duration: i64,
+}
+
+/// Admin-set limits on what creators may choose.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PolicyBounds {
+    pub max_sell_limit_percent: u8,
+    pub max_transfer_limit_percent: u8,
+    pub max_actions_per_day: u8,
+    pub max_windows_per_day: u8,
+    pub min_window_duration: i64,
+    pub max_window_duration: i64,
+}
+
+impl Default for PolicyBounds {
+    /// The protocol's original fixed rules are the ceiling until the admin
+    /// widens it.
+    fn default() -> Self {
+        Self {
+            max_sell_limit_percent: SELL_LIMIT_PERCENT as u8,
+            max_transfer_limit_percent: TRANSFER_LIMIT_PERCENT as u8,
+            max_actions_per_day: 1,
+            max_windows_per_day: 2,
+            min_window_duration: 5 * 60,
+            max_window_duration: WINDOW_DURATION,
+        }
+    }
+}
+
+impl PolicyBounds {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.max_sell_limit_percent <= 100
+                && self.max_transfer_limit_percent <= 100
+                && self.max_actions_per_day >= 1
+                && self.max_windows_per_day >= 1
+                && self.max_windows_per_day as usize <= MAX_WINDOWS_PER_DAY
+                && self.min_window_duration > 0
+                && self.min_window_duration <= self.max_window_duration,
+            LaunchError::InvalidPolicy
+        );
+        Ok(())
+    }
+
+    /// Rejects `params` falling outside these bounds.
+    pub fn check(&self, params: &PolicyParams) -> Result<()> {
+        require!(
+            (1..=self.max_sell_limit_percent).contains(&params.sell_limit_percent)
+                && (1..=self.max_transfer_limit_percent).contains(&params.transfer_limit_percent)
+                && (1..=self.max_actions_per_day).contains(&params.actions_per_day)
+                && (1..=self.max_windows_per_day).contains(&params.windows_per_day)
+                && (self.min_window_duration..=self.max_window_duration)
+                    .contains(&params.window_duration),
+            LaunchError::InvalidPolicy
+        );
+        Ok(())
+    }
+}
+
+/// A holder's remaining sell and transfer allowance over the trailing day.
+/// Reset times are when the oldest counted action stops counting, or 0 when
+/// nothing is counted.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Allowance {
+    pub sell_remaining: u64,
+    pub sell_actions_remaining: u8,
+    pub sell_resets_at: i64,
+    pub transfer_remaining: u64,
+    pub transfer_actions_remaining: u8,
+    pub transfer_resets_at: i64,
+}
+
+/// Per-launch trading rules and the window schedule they produce.
+#[account]
+#[derive(InitSpace)]
+pub struct LaunchPolicy {
+    pub launch_metadata: Pubkey,
+    pub launch_id: u64,
+    pub sell_limit_percent: u8,
+    pub transfer_limit_percent: u8,
+    pub actions_per_day: u8,
+    pub schedule: WindowSchedule,
+    pub bump: u8,
+}
+
+impl LaunchPolicy {
+    /// 8-byte discriminator + fields
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+
+    /// Sell volume allowed per trailing day against a `balance` snapshot.
+    pub fn max_sell(&self, balance: u64) -> Result<u64> {
+        percent_of(balance, self.sell_limit_percent)
+    }
+
+    /// Transfer volume allowed per trailing day against a `balance` snapshot.
+    pub fn max_transfer(&self, balance: u64) -> Result<u64> {
+        percent_of(balance, self.transfer_limit_percent)
+    }
+
+    /// What a holder with these limits and `balance` may still do at `now`.
+    pub fn allowance(
+        &self,
+        sell: &RollingLimit,
+        transfer: &RollingLimit,
+        now: i64,
+        balance: u64,
+    ) -> Result<Allowance> {
+        let sell = sell.at(now, balance);
+        let transfer = transfer.at(now, balance);
+        Ok(Allowance {
+            sell_remaining: sell.remaining(self.max_sell(sell.snapshot_balance)?),
+            sell_actions_remaining: self.actions_per_day.saturating_sub(sell.action_count()),
+            sell_resets_at: sell.resets_at(),
+            transfer_remaining: transfer.remaining(self.max_transfer(transfer.snapshot_balance)?),
+            transfer_actions_remaining: self.actions_per_day.saturating_sub(transfer.action_count()),
+            transfer_resets_at: transfer.resets_at(),
+        })
+    }
+
+    /// Draws `today`'s windows from the oracle's value for this launch.
+    pub fn draw_windows(&mut self, oracle: &RandomnessOracle, today: u64) -> Result<()> {
+        let seed = oracle.window_seed(self.launch_id, today);
+        self.schedule.draw(today, seed)
+    }
+
+    /// Draws the windows for the day of `now` on the first buy, sell or
+    /// transfer once the oracle can seed them. Until then the old windows stay
+    /// closed, since they only apply to the day they were drawn for.
+    pub fn roll_windows(&mut self, oracle: &RandomnessOracle, now: i64) -> Result<()> {
+        let today = day_of(now);
+        if self.schedule.is_stale(today) && oracle.can_seed(now) {
+            self.draw_windows(oracle, today)?;
+        }
+        Ok(())
+    }
+}
+
+fn percent_of(balance: u64, percent: u8) -> Result<u64> {
+    // Widened so balances above u64::MAX / 100 keep their real limit.
+    let share = balance as u128 * percent as u128 / 100;
+    u64::try_from(share).map_err(|_| error!(LaunchError::MathOverflow))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::RANDOMNESS_FALLBACK_DELAY;
+    use crate::state::window_schedule::DAY;
+
+    #[test]
+    fn limits_hold_for_the_largest_balances() {
+        assert_eq!(percent_of(u64::MAX, 100).unwrap(), u64::MAX);
+        assert_eq!(percent_of(u64::MAX, 10).unwrap(), u64::MAX / 10);
+        // A 1e18 supply at 9 decimals held by one wallet
+        assert_eq!(percent_of(1_000_000_000_000_000_000, 20).unwrap(), 200_000_000_000_000_000);
+    }
+
+    #[test]
+    fn windows_fall_back_to_the_last_value_when_a_reveal_is_missed() {
+        let oracle = RandomnessOracle {
+            authority: Pubkey::default(),
+            commitment: [0; 32],
+            committed_slot: 0,
+            pending: false,
+            value: [7; 32],
+            revealed_at: 20_000 * DAY + 100,
+            round: 3,
+            bump: 255,
+        };
+        let schedule = WindowSchedule::spread(2, WINDOW_DURATION).unwrap();
+        let mut policy = LaunchPolicy {
+            launch_metadata: Pubkey::default(),
+            launch_id: 1,
+            sell_limit_percent: 10,
+            transfer_limit_percent: 20,
+            actions_per_day: 1,
+            schedule,
+            bump: 255,
+        };
+
+        let missed = 20_001 * DAY;
+        policy.roll_windows(&oracle, missed + RANDOMNESS_FALLBACK_DELAY - 1).unwrap();
+        assert!(policy.schedule.is_stale(20_001));
+
+        policy.roll_windows(&oracle, missed + RANDOMNESS_FALLBACK_DELAY).unwrap();
+        let mut expected = schedule;
+        expected.draw(20_001, oracle.window_seed(1, 20_001)).unwrap();
+        assert_eq!(policy.schedule, expected);
+    }
+}
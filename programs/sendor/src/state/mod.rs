@@ -1,4 +1,11 @@
 pub mod global_state;
 pub mod launch_metadata;
+pub mod launch_policy;
 pub mod bonding_curve_state;
+pub mod curve_kind;
+pub mod graduation;
+pub mod randomness_oracle;
+pub mod trade_fees;
 pub mod user_record;
+pub mod vesting;
+pub mod window_schedule;
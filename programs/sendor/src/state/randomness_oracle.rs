@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use crate::constants::RANDOMNESS_FALLBACK_DELAY;
+use crate::state::window_schedule::{day_of, DAY};
+
+/// Commit-reveal randomness beacon feeding trading-window selection.
+/// The authority commits `hash(secret)` and reveals `secret` in a later slot,
+/// so nobody else learns the day's value before it is used on-chain. On
+/// localnet a plain keypair plays the authority.
+///
+/// The reveal mixes in the latest slot hash, which the authority does not
+/// control, so secrets cannot be ground offline. The authority still picks the
+/// slot it reveals in and could withhold a value it dislikes, so it is trusted
+/// not to collude with traders; the admin can replace it with
+/// `set_randomness_authority`.
+///
+/// Nor can the authority halt trading by going quiet: once
+/// `RANDOMNESS_FALLBACK_DELAY` of a UTC day passes without a reveal, windows
+/// are drawn from the last revealed value instead. That value is public, so
+/// fallback windows can be worked out in advance; a reveal later that day
+/// does not redraw them.
+#[account]
+#[derive(InitSpace)]
+pub struct RandomnessOracle {
+    /// Key allowed to commit and reveal
+    pub authority: Pubkey,
+    /// `hash(secret)` of the round awaiting reveal
+    pub commitment: [u8; 32],
+    pub committed_slot: u64,
+    /// True between a commit and its reveal
+    pub pending: bool,
+    /// Latest revealed value, chained with every previous one
+    pub value: [u8; 32],
+    pub revealed_at: i64,
+    pub round: u64,
+    pub bump: u8,
+}
+
+impl RandomnessOracle {
+    /// 8-byte discriminator + fields
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+
+    /// Whether a value has been revealed during the UTC day containing `now`.
+    pub fn is_fresh(&self, now: i64) -> bool {
+        self.round > 0 && day_of(self.revealed_at) == day_of(now)
+    }
+
+    /// Whether windows for the day of `now` may be drawn: from today's value
+    /// once revealed, otherwise from the last one after the fallback delay.
+    pub fn can_seed(&self, now: i64) -> bool {
+        self.is_fresh(now) || now.rem_euclid(DAY) >= RANDOMNESS_FALLBACK_DELAY
+    }
+
+    /// Per-launch, per-day seed derived from the revealed value.
+    pub fn window_seed(&self, launch_id: u64, day: u64) -> u64 {
+        let digest = hashv(&[&self.value, &launch_id.to_le_bytes(), &day.to_le_bytes()]);
+        let mut seed = [0u8; 8];
+        seed.copy_from_slice(&digest.to_bytes()[..8]);
+        u64::from_le_bytes(seed)
+    }
+}
@@ -0,0 +1,94 @@
+use anchor_lang::prelude::*;
+use crate::constants::{BPS_DENOMINATOR, MAX_TRADE_FEE_BPS};
+use crate::errors::LaunchError;
+
+/// Basis-point fees charged on every buy and sell.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct TradeFees {
+    /// Share sent to `GlobalState.platform_fee_recipient`
+    pub protocol_bps: u16,
+    /// Share accrued on the launch for its creator
+    pub creator_bps: u16,
+}
+
+/// Lamports taken from one trade, by recipient.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct FeeSplit {
+    pub protocol: u64,
+    pub creator: u64,
+}
+
+impl FeeSplit {
+    pub fn total(&self) -> u64 {
+        self.protocol + self.creator
+    }
+}
+
+impl TradeFees {
+    pub fn validate(&self) -> Result<()> {
+        require!(self.total_bps() <= MAX_TRADE_FEE_BPS, LaunchError::FeeTooHigh);
+        Ok(())
+    }
+
+    pub fn total_bps(&self) -> u64 {
+        self.protocol_bps as u64 + self.creator_bps as u64
+    }
+
+    /// Fees owed on a trade worth `amount` lamports on the curve.
+    pub fn charge(&self, amount: u64) -> FeeSplit {
+        let cut = |bps: u16| (amount as u128 * bps as u128 / BPS_DENOMINATOR as u128) as u64;
+        FeeSplit { protocol: cut(self.protocol_bps), creator: cut(self.creator_bps) }
+    }
+
+    /// Largest curve cost `c` such that `c` plus its fees fits in `gross`.
+    pub fn max_principal(&self, gross: u64) -> u64 {
+        let total = BPS_DENOMINATOR + self.total_bps();
+        let mut principal = (gross as u128 * BPS_DENOMINATOR as u128 / total as u128) as u64;
+        // Per-recipient flooring can leave room for a few more lamports.
+        while principal < gross && (principal + 1).saturating_add(self.charge(principal + 1).total()) <= gross {
+            principal += 1;
+        }
+        principal
+    }
+
+    /// Smallest curve payout `p` such that `p` minus its fees reaches `net`.
+    pub fn min_principal(&self, net: u64) -> Result<u64> {
+        let kept = BPS_DENOMINATOR - self.total_bps();
+        let mut principal = u64::try_from((net as u128 * BPS_DENOMINATOR as u128).div_ceil(kept as u128))
+            .map_err(|_| error!(LaunchError::MathOverflow))?;
+        while principal > net && (principal - 1) - self.charge(principal - 1).total() >= net {
+            principal -= 1;
+        }
+        Ok(principal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn any_fees() -> impl Strategy<Value = TradeFees> {
+        (0..=500u16, 0..=500u16).prop_map(|(protocol_bps, creator_bps)| TradeFees { protocol_bps, creator_bps })
+    }
+
+    proptest! {
+        #[test]
+        fn max_principal_is_tight(fees in any_fees(), gross in any::<u64>()) {
+            let p = fees.max_principal(gross);
+            prop_assert!(p as u128 + fees.charge(p).total() as u128 <= gross as u128);
+            if p < gross {
+                prop_assert!((p + 1) as u128 + fees.charge(p + 1).total() as u128 > gross as u128);
+            }
+        }
+
+        #[test]
+        fn min_principal_is_tight(fees in any_fees(), net in 0..u64::MAX / 2) {
+            let p = fees.min_principal(net)?;
+            prop_assert!(p - fees.charge(p).total() >= net);
+            if p > net {
+                prop_assert!((p - 1) - fees.charge(p - 1).total() < net);
+            }
+        }
+    }
+}
@@ -1,12 +1,164 @@
 use anchor_lang::prelude::*;
+use crate::errors::LaunchError;
+use crate::state::window_schedule::DAY;
+
+/// Length of one sub-bucket of the trailing window.
+pub const HOUR: i64 = 3_600;
+
+/// Hourly sub-buckets making up the trailing day.
+const BUCKETS: usize = (DAY / HOUR) as usize;
+
+/// Per-holder allowance over the trailing 24 hours. Volume is kept in hourly
+/// buckets that each stop counting a day after their hour, so spending the
+/// cap just before one hour boundary leaves nothing to spend just after it.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct RollingLimit {
+    /// Hour since the epoch of the newest bucket (0 = never used)
+    pub last_hour: i64,
+    /// Holder's balance when the window last started from empty
+    pub snapshot_balance: u64,
+    /// Tokens moved in each of the last 24 hours, indexed by hour % 24
+    pub volumes: [u64; 24],
+    /// Actions taken in each of the last 24 hours, indexed by hour % 24
+    pub actions: [u8; 24],
+}
+
+impl RollingLimit {
+    /// The limiter as it stands at `now`, with buckets older than a day
+    /// dropped. An empty window snapshots `balance` afresh.
+    pub fn at(&self, now: i64, balance: u64) -> Self {
+        let hour = now.div_euclid(HOUR);
+        let mut next = if self.last_hour == 0 || hour - self.last_hour >= BUCKETS as i64 {
+            Self { last_hour: hour, ..Self::default() }
+        } else {
+            *self
+        };
+        for expired in next.last_hour + 1..=hour {
+            let slot = Self::slot(expired);
+            next.volumes[slot] = 0;
+            next.actions[slot] = 0;
+        }
+        next.last_hour = next.last_hour.max(hour);
+        if next.action_count() == 0 {
+            next.snapshot_balance = balance;
+        }
+        next
+    }
+
+    /// Tokens moved within the window.
+    pub fn volume(&self) -> u64 {
+        self.volumes.iter().fold(0, |sum, v| sum.saturating_add(*v))
+    }
+
+    /// Actions taken within the window.
+    pub fn action_count(&self) -> u8 {
+        self.actions.iter().fold(0, |sum, a| sum.saturating_add(*a))
+    }
+
+    /// When the oldest counted action stops counting (0 = nothing counted).
+    pub fn resets_at(&self) -> i64 {
+        (self.last_hour + 1 - BUCKETS as i64..=self.last_hour)
+            .find(|hour| self.actions[Self::slot(*hour)] > 0)
+            .map_or(0, |hour| (hour + BUCKETS as i64) * HOUR)
+    }
+
+    /// Tokens still allowed within the window given its cap.
+    pub fn remaining(&self, cap: u64) -> u64 {
+        cap.saturating_sub(self.volume())
+    }
+
+    /// Counts an action moving `amount` tokens out of a `balance`.
+    pub fn record(&mut self, now: i64, balance: u64, amount: u64) -> Result<()> {
+        let mut next = self.at(now, balance);
+        let slot = Self::slot(next.last_hour);
+        next.volumes[slot] = next.volumes[slot].checked_add(amount).ok_or(LaunchError::MathOverflow)?;
+        next.actions[slot] = next.actions[slot].saturating_add(1);
+        *self = next;
+        Ok(())
+    }
+
+    fn slot(hour: i64) -> usize {
+        hour.rem_euclid(BUCKETS as i64) as usize
+    }
+}
 
 #[account]
+#[derive(InitSpace)]
 pub struct UserRecord {
     pub user: Pubkey,
-    pub last_action_day: u64,
+    /// Sells over the holder's trailing day
+    pub sell: RollingLimit,
+    /// Transfers over the holder's trailing day
+    pub transfer: RollingLimit,
 }
 
 impl UserRecord {
-    /// 8-byte discriminator + 32 (user) + 8 (day)
-    pub const LEN: usize = 8 + 32 + 8;
+    /// 8-byte discriminator + fields
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+
+    /// Size of records written before sells and transfers were tracked
+    /// separately: discriminator + user + calendar day of the last action.
+    pub const LEGACY_LEN: usize = 8 + 32 + 8;
+
+    pub fn record_sell(&mut self, user: Pubkey, now: i64, balance: u64, amount: u64) -> Result<()> {
+        self.sell.record(now, balance, amount)?;
+        self.claim(user);
+        Ok(())
+    }
+
+    pub fn record_transfer(&mut self, user: Pubkey, now: i64, balance: u64, amount: u64) -> Result<()> {
+        self.transfer.record(now, balance, amount)?;
+        self.claim(user);
+        Ok(())
+    }
+
+    fn claim(&mut self, user: Pubkey) {
+        if self.user == Pubkey::default() {
+            self.user = user;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn midnight_does_not_reset_the_window() {
+        let midnight = 20_000 * DAY;
+        let mut limit = RollingLimit::default();
+        limit.record(midnight - 60, 1_000, 100).unwrap();
+
+        let later = limit.at(midnight + 60, 900);
+        assert_eq!(later.volume(), 100);
+        assert_eq!(later.snapshot_balance, 1_000);
+        assert_eq!(later.remaining(100), 0);
+    }
+
+    #[test]
+    fn spending_either_side_of_a_reset_stays_within_the_cap() {
+        let start = 20_000 * DAY + 1_234;
+        let mut limit = RollingLimit::default();
+        limit.record(start, 1_000, 40).unwrap();
+
+        let reset = limit.resets_at();
+        limit.record(reset - 1, 1_000, 60).unwrap();
+        assert_eq!(limit.at(reset - 1, 1_000).remaining(100), 0);
+
+        let after = limit.at(reset + 1, 1_000);
+        assert_eq!((after.volume(), after.action_count()), (60, 1));
+        assert_eq!(after.remaining(100), 40);
+        assert_eq!(after.resets_at(), (reset - 1).div_euclid(HOUR) * HOUR + DAY);
+    }
+
+    #[test]
+    fn window_empties_a_day_after_the_last_action() {
+        let start = 20_000 * DAY + 1_234;
+        let mut limit = RollingLimit::default();
+        limit.record(start, 1_000, 100).unwrap();
+
+        let next = limit.at(start + DAY, 900);
+        assert_eq!(next.snapshot_balance, 900);
+        assert_eq!((next.volume(), next.action_count(), next.resets_at()), (0, 0, 0));
+    }
 }
@@ -0,0 +1,151 @@
+use anchor_lang::prelude::*;
+use crate::constants::{BPS_DENOMINATOR, MAX_VESTED_SUPPLY_BPS, MAX_VESTING_ALLOCATIONS};
+use crate::errors::LaunchError;
+
+pub const VESTING_SEED: &[u8] = b"vesting";
+
+/// An allocation reserved for the creator or team at `create_launch`. Nothing
+/// unlocks before `cliff`; after that the grant vests linearly until
+/// `duration`, both in seconds from the launch.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct VestingParams {
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+    pub cliff: i64,
+    pub duration: i64,
+}
+
+impl VestingParams {
+    pub fn validate(&self) -> Result<()> {
+        require_keys_neq!(self.beneficiary, Pubkey::default(), LaunchError::InvalidVesting);
+        require!(self.amount > 0, LaunchError::InvalidVesting);
+        require!(
+            self.duration > 0 && (0..=self.duration).contains(&self.cliff),
+            LaunchError::InvalidVesting
+        );
+        Ok(())
+    }
+
+    /// Validates a launch's allocations out of `supply` and returns how much
+    /// they hold back from the curve.
+    pub fn total(allocations: &[Self], supply: u64) -> Result<u64> {
+        require!(allocations.len() <= MAX_VESTING_ALLOCATIONS, LaunchError::InvalidVesting);
+        let mut total = 0u64;
+        for (i, allocation) in allocations.iter().enumerate() {
+            allocation.validate()?;
+            require!(
+                allocations[..i].iter().all(|a| a.beneficiary != allocation.beneficiary),
+                LaunchError::InvalidVesting
+            );
+            total = total.checked_add(allocation.amount).ok_or(LaunchError::MathOverflow)?;
+        }
+        require!(
+            total as u128 * BPS_DENOMINATOR as u128 <= supply as u128 * MAX_VESTED_SUPPLY_BPS as u128,
+            LaunchError::InvalidVesting
+        );
+        Ok(total)
+    }
+}
+
+/// Tokens held for one beneficiary of a launch, at
+/// `[VESTING_SEED, launch_metadata, beneficiary]`. The schedule owns
+/// `token_account` and outlives the launch accounts closed at migration.
+#[account]
+#[derive(InitSpace)]
+pub struct VestingSchedule {
+    pub launch_metadata: Pubkey,
+    pub beneficiary: Pubkey,
+    /// May revoke what has not vested yet; the launch creator at the time
+    pub authority: Pubkey,
+    pub token_mint: Pubkey,
+    pub token_account: Pubkey,
+    /// Tokens granted; cut to what had vested when the schedule is revoked
+    pub total_amount: u64,
+    pub claimed_amount: u64,
+    pub start: i64,
+    pub cliff: i64,
+    pub duration: i64,
+    pub revoked: bool,
+    pub bump: u8,
+}
+
+impl VestingSchedule {
+    /// 8-byte discriminator + fields
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+
+    /// Tokens unlocked by `now`, claimed or not.
+    pub fn vested_at(&self, now: i64) -> Result<u64> {
+        let elapsed = now.saturating_sub(self.start);
+        if self.revoked || elapsed >= self.duration {
+            return Ok(self.total_amount);
+        }
+        if elapsed < self.cliff {
+            return Ok(0);
+        }
+        let vested = self.total_amount as u128 * elapsed as u128 / self.duration as u128;
+        u64::try_from(vested).map_err(|_| error!(LaunchError::MathOverflow))
+    }
+
+    pub fn claimable(&self, now: i64) -> Result<u64> {
+        Ok(self.vested_at(now)?.saturating_sub(self.claimed_amount))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn schedule(total_amount: u64, cliff: i64, duration: i64) -> VestingSchedule {
+        VestingSchedule {
+            launch_metadata: Pubkey::new_unique(),
+            beneficiary: Pubkey::new_unique(),
+            authority: Pubkey::new_unique(),
+            token_mint: Pubkey::new_unique(),
+            token_account: Pubkey::new_unique(),
+            total_amount,
+            claimed_amount: 0,
+            start: 1_000,
+            cliff,
+            duration,
+            revoked: false,
+            bump: 255,
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn vesting_is_monotonic_and_bounded(
+            total in any::<u64>(),
+            (cliff, duration) in (1..=i64::MAX / 4).prop_flat_map(|d| (0..=d, Just(d))),
+            a in any::<i64>(),
+            b in any::<i64>(),
+        ) {
+            let s = schedule(total, cliff, duration);
+            let (early, late) = (a.min(b), a.max(b));
+            prop_assert!(s.vested_at(early)? <= s.vested_at(late)?);
+            prop_assert!(s.vested_at(late)? <= total);
+            if early < s.start + cliff {
+                prop_assert_eq!(s.vested_at(early)?, 0);
+            }
+        }
+    }
+
+    #[test]
+    fn cliff_then_linear_unlock() {
+        let mut s = schedule(1_000, 100, 400);
+        assert_eq!(s.vested_at(1_099).unwrap(), 0);
+        assert_eq!(s.vested_at(1_100).unwrap(), 250);
+        assert_eq!(s.vested_at(1_300).unwrap(), 750);
+        assert_eq!(s.vested_at(1_400).unwrap(), 1_000);
+
+        s.claimed_amount = 250;
+        assert_eq!(s.claimable(1_300).unwrap(), 500);
+
+        // Revoking freezes the grant at what had vested.
+        s.total_amount = s.vested_at(1_300).unwrap();
+        s.revoked = true;
+        assert_eq!(s.vested_at(1_400).unwrap(), 750);
+        assert_eq!(s.claimable(i64::MAX).unwrap(), 500);
+    }
+}
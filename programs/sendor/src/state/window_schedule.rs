@@ -0,0 +1,162 @@
+use anchor_lang::prelude::*;
+use crate::constants::{HALF_DAY, WINDOW_DURATION};
+use crate::errors::LaunchError;
+
+pub const DAY: i64 = 86_400;
+
+/// Upper bound on trading windows per day.
+pub const MAX_WINDOWS_PER_DAY: usize = 4;
+
+/// A launch's daily trading windows: how many there are, how long they last
+/// and where today's fall. Starts are offsets from UTC midnight.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct WindowSchedule {
+    /// Windows drawn each day
+    pub count: u8,
+    /// Length of each window in seconds
+    pub duration: i64,
+    /// Minimum spacing between the starts of consecutive windows
+    pub min_gap: i64,
+    /// UTC day the current starts were drawn for (0 = never drawn)
+    pub day: u64,
+    /// Only `starts[..count]` are meaningful
+    pub starts: [i64; MAX_WINDOWS_PER_DAY],
+}
+
+impl Default for WindowSchedule {
+    /// Two 15-minute windows at least half a day apart.
+    fn default() -> Self {
+        Self {
+            count: 2,
+            duration: WINDOW_DURATION,
+            min_gap: HALF_DAY,
+            day: 0,
+            starts: [0; MAX_WINDOWS_PER_DAY],
+        }
+    }
+}
+
+/// UTC day number containing `now`.
+pub fn day_of(now: i64) -> u64 {
+    now.div_euclid(DAY) as u64
+}
+
+/// splitmix64 step, used to stretch one seed over several windows.
+fn next_random(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+impl WindowSchedule {
+    /// A schedule with the given shape and nothing drawn yet.
+    pub fn new(count: u8, duration: i64, min_gap: i64) -> Result<Self> {
+        let schedule = Self { count, duration, min_gap, ..Self::default() };
+        schedule.validate()?;
+        Ok(schedule)
+    }
+
+    /// `count` windows of `duration`, spaced so that together they can span at
+    /// most half the day.
+    pub fn spread(count: u8, duration: i64) -> Result<Self> {
+        let min_gap = match count {
+            0 | 1 => duration,
+            n => (DAY / (2 * (i64::from(n) - 1))).max(duration),
+        };
+        Self::new(count, duration, min_gap)
+    }
+
+    /// Every window must fit in one day without overlapping the next.
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.count >= 1 && self.count as usize <= MAX_WINDOWS_PER_DAY,
+            LaunchError::InvalidWindowTimes
+        );
+        require!(self.duration > 0 && self.min_gap >= self.duration, LaunchError::InvalidWindowTimes);
+        require!(self.slack().is_some_and(|s| s >= 0), LaunchError::InvalidWindowTimes);
+        Ok(())
+    }
+
+    /// Room left in the day once the windows are packed as tightly as allowed.
+    fn slack(&self) -> Option<i64> {
+        let gaps = self.min_gap.checked_mul(i64::from(self.count).checked_sub(1)?)?;
+        DAY.checked_sub(gaps)?.checked_sub(self.duration)
+    }
+
+    pub fn windows(&self) -> &[i64] {
+        &self.starts[..self.count as usize]
+    }
+
+    /// Whether `today`'s windows still have to be drawn.
+    pub fn is_stale(&self, today: u64) -> bool {
+        today > self.day
+    }
+
+    /// Draws `day`'s windows from `seed`. Offsets are spread uniformly over the
+    /// slack, sorted, then pushed apart by `min_gap`, so every layout that
+    /// respects the spacing is equally likely.
+    pub fn draw(&mut self, day: u64, seed: u64) -> Result<()> {
+        self.validate()?;
+        let slack = self.slack().ok_or(LaunchError::InvalidWindowTimes)? as u64;
+
+        let mut state = seed;
+        let mut offsets = [0i64; MAX_WINDOWS_PER_DAY];
+        let offsets = &mut offsets[..self.count as usize];
+        for offset in offsets.iter_mut() {
+            *offset = (next_random(&mut state) % (slack + 1)) as i64;
+        }
+        offsets.sort_unstable();
+
+        self.starts = [0; MAX_WINDOWS_PER_DAY];
+        for (i, offset) in offsets.iter().enumerate() {
+            self.starts[i] = offset + self.min_gap * i as i64;
+        }
+        self.day = day;
+        Ok(())
+    }
+
+    /// Whether `now` falls inside one of the windows drawn for its day.
+    pub fn is_open(&self, now: i64) -> bool {
+        if day_of(now) != self.day {
+            return false;
+        }
+        let t = now.rem_euclid(DAY);
+        self.windows().iter().any(|&start| t >= start && t < start + self.duration)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn drawn_windows_fit_the_day_and_keep_their_spacing(
+            count in 1u8..=MAX_WINDOWS_PER_DAY as u8,
+            duration in 60i64..=3_600,
+            min_gap in 3_600i64..=43_200,
+            day in 1u64..100_000,
+            seed: u64,
+        ) {
+            let Ok(mut schedule) = WindowSchedule::new(count, duration, min_gap) else {
+                return Ok(());
+            };
+            schedule.draw(day, seed).unwrap();
+
+            let windows = schedule.windows();
+            prop_assert!(windows[0] >= 0);
+            prop_assert!(windows[windows.len() - 1] + duration <= DAY);
+            for pair in windows.windows(2) {
+                prop_assert!(pair[1] - pair[0] >= min_gap);
+            }
+            for &start in windows {
+                let at = day as i64 * DAY + start;
+                prop_assert!(schedule.is_open(at));
+                prop_assert!(!schedule.is_open(at + DAY));
+            }
+        }
+    }
+}
@@ -0,0 +1,391 @@
+//! Per-launch mint setup for the legacy token program and Token-2022.
+//!
+//! A launch picks its token program and, on Token-2022, which extensions its
+//! mint carries. The mint is created by hand because the extensions have to
+//! be initialized between allocating the account and `initialize_mint2`.
+//! With the metadata pointer on, the token's name, symbol and URI are stored
+//! in the mint itself so wallets and explorers can read them.
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token::spl_token;
+use anchor_spl::token_interface::{
+    self, spl_pod::optional_keys::OptionalNonZeroPubkey, spl_token_2022,
+    spl_token_metadata_interface::state::{Field, TokenMetadata}, HarvestWithheldTokensToMint,
+    InitializeMint2,
+    MetadataPointerInitialize, TokenMetadataInitialize, TokenMetadataUpdateField,
+    TransferFeeInitialize, TransferHookInitialize,
+};
+use spl_token_2022::extension::{
+    transfer_fee::TransferFeeConfig, transfer_hook, BaseStateWithExtensions, ExtensionType,
+    StateWithExtensions,
+};
+use anchor_lang::solana_program::program_pack::Pack;
+
+use crate::constants::{MAX_TRANSFER_FEE_BPS, TRANSFER_HOOK_PROGRAM_ID};
+use crate::errors::LaunchError;
+
+/// Fee Token-2022 withholds from every transfer of the mint.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TransferFeeParams {
+    pub basis_points: u16,
+    /// Cap on the fee for a single transfer, in base units
+    pub maximum_fee: u64,
+}
+
+/// Token-2022 extensions a creator picks at `create_launch`. All must be off
+/// on the legacy token program.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct TokenExtensions {
+    /// Store the token's name, symbol and URI in the mint itself
+    pub metadata_pointer: bool,
+    /// Fee on every transfer, withdrawable by the creator
+    pub transfer_fee: Option<TransferFeeParams>,
+    /// Enforce the launch's transfer limits on direct wallet transfers
+    pub transfer_hook: bool,
+}
+
+impl TokenExtensions {
+    pub fn extension_types(&self) -> Vec<ExtensionType> {
+        let mut types = Vec::new();
+        if self.metadata_pointer {
+            types.push(ExtensionType::MetadataPointer);
+        }
+        if self.transfer_fee.is_some() {
+            types.push(ExtensionType::TransferFeeConfig);
+        }
+        if self.transfer_hook {
+            types.push(ExtensionType::TransferHook);
+        }
+        types
+    }
+
+    pub fn validate(&self, token_program: &Pubkey) -> Result<()> {
+        if *token_program == spl_token::ID {
+            require!(*self == Self::default(), LaunchError::UnsupportedExtension);
+        } else {
+            require_keys_eq!(*token_program, spl_token_2022::ID, LaunchError::UnsupportedExtension);
+        }
+        if let Some(fee) = self.transfer_fee {
+            require!(fee.basis_points <= MAX_TRANSFER_FEE_BPS, LaunchError::InvalidTransferFee);
+        }
+        Ok(())
+    }
+
+    /// Bytes the mint needs under `token_program` with these extensions.
+    pub fn mint_space(&self, token_program: &Pubkey) -> Result<usize> {
+        self.validate(token_program)?;
+        if *token_program == spl_token::ID {
+            return Ok(spl_token::state::Mint::LEN);
+        }
+        ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(
+            &self.extension_types(),
+        )
+        .map_err(Into::into)
+    }
+}
+
+/// Accounts for `create_mint`.
+pub struct CreateMint<'info> {
+    /// Funds the mint and may withdraw its withheld transfer fees
+    pub payer: AccountInfo<'info>,
+    pub mint: AccountInfo<'info>,
+    /// Mint, freeze and extension authority
+    pub authority: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+}
+
+/// Allocates `accounts.mint` under the chosen token program, initializes the
+/// selected extensions and then the mint itself.
+pub fn create_mint<'info>(
+    accounts: CreateMint<'info>,
+    extensions: &TokenExtensions,
+    decimals: u8,
+) -> Result<()> {
+    let token_program = accounts.token_program.key();
+    let space = extensions.mint_space(&token_program)?;
+    system_program::create_account(
+        CpiContext::new(
+            accounts.system_program.clone(),
+            system_program::CreateAccount {
+                from: accounts.payer.clone(),
+                to: accounts.mint.clone(),
+            },
+        ),
+        Rent::get()?.minimum_balance(space),
+        space as u64,
+        &token_program,
+    )?;
+
+    let program = &accounts.token_program;
+    let authority = accounts.authority.key();
+    if extensions.metadata_pointer {
+        token_interface::metadata_pointer_initialize(
+            CpiContext::new(
+                program.clone(),
+                MetadataPointerInitialize {
+                    token_program_id: program.clone(),
+                    mint: accounts.mint.clone(),
+                },
+            ),
+            Some(authority),
+            Some(accounts.mint.key()),
+        )?;
+    }
+    if let Some(fee) = extensions.transfer_fee {
+        // No config authority, so the fee buyers saw at launch can't be raised.
+        token_interface::transfer_fee_initialize(
+            CpiContext::new(
+                program.clone(),
+                TransferFeeInitialize {
+                    token_program_id: program.clone(),
+                    mint: accounts.mint.clone(),
+                },
+            ),
+            None,
+            Some(accounts.payer.key),
+            fee.basis_points,
+            fee.maximum_fee,
+        )?;
+    }
+    if extensions.transfer_hook {
+        token_interface::transfer_hook_initialize(
+            CpiContext::new(
+                program.clone(),
+                TransferHookInitialize {
+                    token_program_id: program.clone(),
+                    mint: accounts.mint.clone(),
+                },
+            ),
+            Some(authority),
+            Some(TRANSFER_HOOK_PROGRAM_ID),
+        )?;
+    }
+
+    token_interface::initialize_mint2(
+        CpiContext::new(program.clone(), InitializeMint2 { mint: accounts.mint }),
+        decimals,
+        &authority,
+        Some(&authority),
+    )
+}
+
+/// Fee `mint` withholds from a transfer of `amount` this epoch.
+pub fn transfer_fee(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    let Some(config) = fee_config(mint)? else {
+        return Ok(0);
+    };
+    let epoch = Clock::get()?.epoch;
+    config
+        .calculate_epoch_fee(epoch, amount)
+        .ok_or(error!(LaunchError::MathOverflow))
+}
+
+/// Smallest transfer of `mint` that still delivers `received` after the fee.
+pub fn gross_up(mint: &AccountInfo, received: u64) -> Result<u64> {
+    let Some(config) = fee_config(mint)? else {
+        return Ok(received);
+    };
+    let epoch = Clock::get()?.epoch;
+    config
+        .calculate_inverse_epoch_fee(epoch, received)
+        .and_then(|fee| received.checked_add(fee))
+        .ok_or(error!(LaunchError::MathOverflow))
+}
+
+/// Whether transfers of `mint` run sendor's hook, which then enforces the
+/// launch's transfer limit on every transfer.
+pub fn has_sendor_hook(mint: &AccountInfo) -> Result<bool> {
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(false);
+    }
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    Ok(transfer_hook::get_program_id(&state) == Some(TRANSFER_HOOK_PROGRAM_ID))
+}
+
+fn fee_config(mint: &AccountInfo) -> Result<Option<TransferFeeConfig>> {
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(None);
+    }
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    Ok(state.get_extension::<TransferFeeConfig>().ok().copied())
+}
+
+/// Sweeps the transfer fees withheld in `account` into the mint, which has to
+/// happen before the account can be closed. Mints without a fee are skipped.
+pub fn harvest_withheld<'info>(
+    token_program: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    account: AccountInfo<'info>,
+) -> Result<()> {
+    if fee_config(&mint)?.is_none() {
+        return Ok(());
+    }
+    token_interface::harvest_withheld_tokens_to_mint(
+        CpiContext::new(
+            token_program.clone(),
+            HarvestWithheldTokensToMint { token_program_id: token_program, mint },
+        ),
+        vec![account],
+    )
+}
+
+/// Accounts for writing the token metadata stored in the mint.
+pub struct MintMetadata<'info> {
+    /// Covers the extra rent as the mint grows
+    pub payer: AccountInfo<'info>,
+    pub mint: AccountInfo<'info>,
+    /// Mint authority and metadata update authority
+    pub authority: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+}
+
+/// Writes `name`, `symbol` and `uri` into a mint whose metadata pointer aims
+/// at itself.
+pub fn init_token_metadata<'info>(
+    accounts: MintMetadata<'info>,
+    name: String,
+    symbol: String,
+    uri: String,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let metadata = TokenMetadata {
+        update_authority: OptionalNonZeroPubkey(accounts.authority.key()),
+        mint: accounts.mint.key(),
+        name: name.clone(),
+        symbol: symbol.clone(),
+        uri: uri.clone(),
+        additional_metadata: Vec::new(),
+    };
+    fund_growth(&accounts, metadata.tlv_size_of()?)?;
+
+    token_interface::token_metadata_initialize(
+        CpiContext::new_with_signer(
+            accounts.token_program.clone(),
+            TokenMetadataInitialize {
+                program_id: accounts.token_program,
+                metadata: accounts.mint.clone(),
+                update_authority: accounts.authority.clone(),
+                mint_authority: accounts.authority,
+                mint: accounts.mint,
+            },
+            signer_seeds,
+        ),
+        name,
+        symbol,
+        uri,
+    )
+}
+
+/// Replaces the URI in the mint's metadata. Mints without embedded metadata
+/// have no URI to replace.
+pub fn update_token_uri<'info>(
+    accounts: MintMetadata<'info>,
+    uri: String,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let Some(current) = token_metadata(&accounts.mint)? else {
+        return err!(LaunchError::MetadataWithoutPointer);
+    };
+    let mut updated = current.clone();
+    updated.update(Field::Uri, uri.clone());
+    fund_growth(&accounts, updated.tlv_size_of()?.saturating_sub(current.tlv_size_of()?))?;
+
+    token_interface::token_metadata_update_field(
+        CpiContext::new_with_signer(
+            accounts.token_program.clone(),
+            TokenMetadataUpdateField {
+                program_id: accounts.token_program,
+                metadata: accounts.mint,
+                update_authority: accounts.authority,
+            },
+            signer_seeds,
+        ),
+        Field::Uri,
+        uri,
+    )
+}
+
+fn token_metadata(mint: &AccountInfo) -> Result<Option<TokenMetadata>> {
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(None);
+    }
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    Ok(state.get_variable_len_extension::<TokenMetadata>().ok())
+}
+
+/// Token-2022 reallocates the mint itself but expects the rent to be there.
+fn fund_growth(accounts: &MintMetadata, extra: usize) -> Result<()> {
+    let needed = Rent::get()?.minimum_balance(accounts.mint.data_len() + extra);
+    let shortfall = needed.saturating_sub(accounts.mint.lamports());
+    if shortfall == 0 {
+        return Ok(());
+    }
+    system_program::transfer(
+        CpiContext::new(
+            accounts.system_program.clone(),
+            system_program::Transfer {
+                from: accounts.payer.clone(),
+                to: accounts.mint.clone(),
+            },
+        ),
+        shortfall,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FEE: TransferFeeParams = TransferFeeParams { basis_points: 100, maximum_fee: 1_000 };
+
+    #[test]
+    fn legacy_program_takes_plain_mints_only() {
+        let plain = TokenExtensions::default();
+        assert_eq!(plain.mint_space(&spl_token::ID).unwrap(), spl_token::state::Mint::LEN);
+
+        let with_hook = TokenExtensions { transfer_hook: true, ..plain };
+        let with_fee = TokenExtensions { transfer_fee: Some(FEE), ..plain };
+        let with_pointer = TokenExtensions { metadata_pointer: true, ..plain };
+        for extensions in [with_hook, with_fee, with_pointer] {
+            assert!(extensions.validate(&spl_token::ID).is_err());
+        }
+    }
+
+    #[test]
+    fn token_2022_sizes_the_mint_for_its_extensions() {
+        let plain = TokenExtensions::default();
+        let all = TokenExtensions {
+            metadata_pointer: true,
+            transfer_fee: Some(FEE),
+            transfer_hook: true,
+        };
+        assert_eq!(all.extension_types().len(), 3);
+
+        let base = plain.mint_space(&spl_token_2022::ID).unwrap();
+        let full = all.mint_space(&spl_token_2022::ID).unwrap();
+        assert_eq!(base, spl_token_2022::state::Mint::LEN);
+        assert_eq!(
+            full,
+            ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[
+                ExtensionType::MetadataPointer,
+                ExtensionType::TransferFeeConfig,
+                ExtensionType::TransferHook,
+            ])
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn rejects_excessive_fees_and_unknown_programs() {
+        let steep = TransferFeeParams { basis_points: MAX_TRANSFER_FEE_BPS + 1, ..FEE };
+        let extensions = TokenExtensions { transfer_fee: Some(steep), ..Default::default() };
+        assert!(extensions.validate(&spl_token_2022::ID).is_err());
+        assert!(TokenExtensions::default().validate(&Pubkey::new_unique()).is_err());
+    }
+}
@@ -0,0 +1,167 @@
+//! CPI helpers for the launch's Token-2022 transfer hook.
+//!
+//! Launch mints may carry a transfer hook, so token movements made by this
+//! program forward the hook's extra accounts. Clients resolve them from the
+//! mint's validation account and pass them as remaining accounts; for mints
+//! without a hook there are none.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    hash::hash,
+    instruction::{AccountMeta, Instruction},
+    program::invoke_signed,
+};
+use anchor_spl::token_interface::{spl_token_2022, TransferChecked};
+use crate::constants::TRANSFER_HOOK_PROGRAM_ID;
+use crate::errors::LaunchError;
+use crate::state::user_record::RollingLimit;
+use crate::token_extensions;
+
+/// Seed of the hook's validation account, fixed by the transfer-hook interface.
+pub const EXTRA_ACCOUNT_METAS_SEED: &[u8] = b"extra-account-metas";
+
+/// Offset of the rolling limit in the hook's transfer record: discriminator,
+/// owner and mint come first.
+const RECORD_LIMIT_OFFSET: usize = 8 + 32 + 32;
+
+/// `transfer_checked` that also resolves and forwards the hook's accounts from
+/// `ctx.remaining_accounts`.
+pub fn transfer_checked<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, TransferChecked<'info>>,
+    amount: u64,
+    decimals: u8,
+) -> Result<()> {
+    spl_token_2022::onchain::invoke_transfer_checked(
+        ctx.program.key,
+        ctx.accounts.from,
+        ctx.accounts.mint,
+        ctx.accounts.to,
+        ctx.accounts.authority,
+        &ctx.remaining_accounts,
+        amount,
+        decimals,
+        ctx.signer_seeds,
+    )
+    .map_err(Into::into)
+}
+
+/// Accounts for the hook's `initialize_extra_account_meta_list`.
+pub struct InitializeHook<'info> {
+    pub payer: AccountInfo<'info>,
+    pub extra_account_meta_list: AccountInfo<'info>,
+    pub mint: AccountInfo<'info>,
+    /// Mint authority; the hook only accepts a list signed by it
+    pub launch_metadata: AccountInfo<'info>,
+    pub launch_policy: AccountInfo<'info>,
+    pub global_state: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+}
+
+/// Has the hook program write the launch's validation account. Built by hand
+/// because the hook crate depends on this one for its account types.
+pub fn initialize_extra_account_meta_list<'info>(
+    hook_program: AccountInfo<'info>,
+    accounts: InitializeHook<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let ix = Instruction {
+        program_id: hook_program.key(),
+        accounts: vec![
+            AccountMeta::new(accounts.payer.key(), true),
+            AccountMeta::new(accounts.extra_account_meta_list.key(), false),
+            AccountMeta::new_readonly(accounts.mint.key(), false),
+            AccountMeta::new_readonly(accounts.launch_metadata.key(), true),
+            AccountMeta::new_readonly(accounts.launch_policy.key(), false),
+            AccountMeta::new_readonly(accounts.global_state.key(), false),
+            AccountMeta::new_readonly(accounts.system_program.key(), false),
+        ],
+        data: hash(b"global:initialize_extra_account_meta_list").to_bytes()[..8].to_vec(),
+    };
+    invoke_signed(
+        &ix,
+        &[
+            accounts.payer,
+            accounts.extra_account_meta_list,
+            accounts.mint,
+            accounts.launch_metadata,
+            accounts.launch_policy,
+            accounts.global_state,
+            accounts.system_program,
+            hook_program,
+        ],
+        signer_seeds,
+    )
+    .map_err(Into::into)
+}
+
+/// Accounts for the hook's `init_transfer_record`.
+pub struct InitTransferRecord<'info> {
+    pub payer: AccountInfo<'info>,
+    pub mint: AccountInfo<'info>,
+    pub transfer_record: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+}
+
+/// Has the hook program open `owner`'s transfer record for the mint, unless
+/// it already exists.
+pub fn init_transfer_record<'info>(
+    hook_program: AccountInfo<'info>,
+    accounts: InitTransferRecord<'info>,
+    owner: Pubkey,
+) -> Result<()> {
+    if !accounts.transfer_record.data_is_empty() {
+        return Ok(());
+    }
+    let mut data = hash(b"global:init_transfer_record").to_bytes()[..8].to_vec();
+    data.extend_from_slice(owner.as_ref());
+    let ix = Instruction {
+        program_id: hook_program.key(),
+        accounts: vec![
+            AccountMeta::new(accounts.payer.key(), true),
+            AccountMeta::new_readonly(accounts.mint.key(), false),
+            AccountMeta::new(accounts.transfer_record.key(), false),
+            AccountMeta::new_readonly(accounts.system_program.key(), false),
+        ],
+        data,
+    };
+    invoke_signed(
+        &ix,
+        &[
+            accounts.payer,
+            accounts.mint,
+            accounts.transfer_record,
+            accounts.system_program,
+            hook_program,
+        ],
+        &[],
+    )
+    .map_err(Into::into)
+}
+
+/// A holder's transfers over the trailing day. With sendor's hook on `mint`
+/// the hook counts them in `transfer_record`, which is empty until the first;
+/// otherwise sendor's own `tracked` count applies.
+pub fn transfer_usage(
+    mint: &AccountInfo,
+    transfer_record: Option<&AccountInfo>,
+    tracked: RollingLimit,
+) -> Result<RollingLimit> {
+    if !token_extensions::has_sendor_hook(mint)? {
+        return Ok(tracked);
+    }
+    let record = transfer_record.ok_or(LaunchError::MissingHookAccounts)?;
+    if record.data_is_empty() {
+        return Ok(RollingLimit::default());
+    }
+    require_keys_eq!(*record.owner, TRANSFER_HOOK_PROGRAM_ID, LaunchError::MissingHookAccounts);
+    record_limit(record)
+}
+
+/// Reads the rolling limit the hook keeps in a transfer record.
+fn record_limit(transfer_record: &AccountInfo) -> Result<RollingLimit> {
+    let data = transfer_record.try_borrow_data()?;
+    let limit = data
+        .get(RECORD_LIMIT_OFFSET..)
+        .ok_or(error!(ErrorCode::AccountDidNotDeserialize))?;
+    RollingLimit::deserialize(&mut &limit[..]).map_err(Into::into)
+}
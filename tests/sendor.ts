@@ -8,10 +8,16 @@ import {
   LAMPORTS_PER_SOL,
 } from "@solana/web3.js";
 import {
-  createMint,
   getOrCreateAssociatedTokenAccount,
+  getAssociatedTokenAddressSync,
   getMint,
   getAccount,
+  getMetadataPointerState,
+  getTransferFeeConfig,
+  getTransferHook,
+  getTokenMetadata,
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
 } from "@solana/spl-token";
 import { Sendor } from "../target/types/sendor";
 
@@ -26,10 +32,6 @@ describe("sendor - initialization and launch tests", () => {
   const unauthorizedUser = Keypair.generate();
   
   let globalStatePda: PublicKey;
-  let launchMetadataPda: PublicKey;
-  let bondingCurvePda: PublicKey;
-  let tokenMint: PublicKey;
-  let vault: PublicKey;
 
   before(async () => {
     // Setup: Airdrop SOL to admin and unauthorized user
@@ -43,13 +45,13 @@ describe("sendor - initialization and launch tests", () => {
   describe("Initialize Global State", () => {
     it("should initialize global state with correct admin", async () => {
       [globalStatePda] = PublicKey.findProgramAddressSync(
-        [Buffer.from("global")],
+        [Buffer.from("global_v2")],
         program.programId
       );
 
       await program.methods
-        .initialize()
-        .accounts({
+        .initialize(adminKeypair.publicKey, new anchor.BN(0))
+        .accountsPartial({
           globalState: globalStatePda,
           admin: adminKeypair.publicKey,
           systemProgram: SystemProgram.programId,
@@ -67,8 +69,8 @@ describe("sendor - initialization and launch tests", () => {
     it("should fail initializing global state again", async () => {
       try {
         await program.methods
-          .initialize()
-          .accounts({
+          .initialize(adminKeypair.publicKey, new anchor.BN(0))
+          .accountsPartial({
             globalState: globalStatePda,
             admin: adminKeypair.publicKey,
             systemProgram: SystemProgram.programId,
@@ -78,7 +80,75 @@ describe("sendor - initialization and launch tests", () => {
           .rpc();
         assert.fail("Should not allow second initialization");
       } catch (error) {
-        assert.include(error.message, "Error Code: AccountAlreadyInitialized");
+        // `init` fails inside the system program, which reports the address as taken.
+        assert.match([error.message, ...(error.logs ?? [])].join("\n"), /already in use/);
+      }
+    });
+  });
+
+  describe("Randomness Oracle", () => {
+    // A local keypair stands in for the oracle authority on localnet.
+    const oracleAuthority = Keypair.generate();
+    const secret = Keypair.generate().publicKey.toBuffer();
+    let randomnessPda: PublicKey;
+
+    const sha256 = (data: Buffer) =>
+      Array.from(require("crypto").createHash("sha256").update(data).digest() as Buffer);
+
+    it("should commit and reveal a value", async () => {
+      [randomnessPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("randomness")],
+        program.programId
+      );
+
+      await program.methods
+        .initRandomnessOracle(oracleAuthority.publicKey)
+        .accountsPartial({
+          globalState: globalStatePda,
+          randomness: randomnessPda,
+          admin: adminKeypair.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([adminKeypair])
+        .rpc();
+
+      await program.methods
+        .commitRandomness(sha256(secret))
+        .accountsPartial({ randomness: randomnessPda, authority: oracleAuthority.publicKey })
+        .signers([oracleAuthority])
+        .rpc();
+
+      // The reveal has to land in a later slot than the commit.
+      await new Promise(resolve => setTimeout(resolve, 1000));
+
+      await program.methods
+        .revealRandomness(Array.from(secret))
+        .accountsPartial({ randomness: randomnessPda, authority: oracleAuthority.publicKey })
+        .signers([oracleAuthority])
+        .rpc();
+
+      const oracle = await program.account.randomnessOracle.fetch(randomnessPda);
+      assert.equal(oracle.round.toNumber(), 1);
+      assert.isFalse(oracle.pending);
+    });
+
+    it("should reject a reveal that does not match the commitment", async () => {
+      await program.methods
+        .commitRandomness(sha256(secret))
+        .accountsPartial({ randomness: randomnessPda, authority: oracleAuthority.publicKey })
+        .signers([oracleAuthority])
+        .rpc();
+      await new Promise(resolve => setTimeout(resolve, 1000));
+
+      try {
+        await program.methods
+          .revealRandomness(Array.from(Keypair.generate().publicKey.toBuffer()))
+          .accountsPartial({ randomness: randomnessPda, authority: oracleAuthority.publicKey })
+          .signers([oracleAuthority])
+          .rpc();
+        assert.fail("Should reject a mismatched reveal");
+      } catch (error) {
+        assert.include(error.message, "InvalidRandomness");
       }
     });
   });
@@ -86,160 +156,607 @@ describe("sendor - initialization and launch tests", () => {
   describe("Create Launch", () => {
     const basePrice = 1; // 1 lamport
     const slope = 1;
+    const tokenName = "Sendor Test";
+    const tokenSymbol = "SNDT";
+    const tokenUri = "https://example.com/sendor.json";
+    const policy = {
+      sellLimitPercent: 10,
+      transferLimitPercent: 20,
+      actionsPerDay: 1,
+      windowsPerDay: 2,
+      windowDuration: new anchor.BN(15 * 60),
+    };
+    const noExtensions = { metadataPointer: false, transferFee: null, transferHook: false };
+    const supply = { totalSupply: new anchor.BN("1000000000000000000"), decimals: 9, saleBps: 8000 };
+    const graduation = { supplySold: { 0: new anchor.BN("800000000000000000") } };
     
-    it("should create a new token launch successfully", async () => {
-      const seed = new anchor.BN(0).toArrayLike(Buffer, "le", 8);
+    const hookProgramId = new PublicKey("J5EzMNEkgUcnyaAQkrUyPkYhbNj9pXnQPXLLvqm2cwv7");
 
-      [launchMetadataPda] = PublicKey.findProgramAddressSync(
-        [Buffer.from("launch"), seed],
+    const transferRecord = (mint: PublicKey, owner: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("record"), mint.toBuffer(), owner.toBuffer()],
+        hookProgramId
+      )[0];
+
+    // Extra accounts the token program needs to run the hook on a transfer
+    // out of an account owned by `owner`.
+    const hookAccounts = (launch: PublicKey, launchPolicy: PublicKey, mint: PublicKey, owner: PublicKey) =>
+      [
+        PublicKey.findProgramAddressSync(
+          [Buffer.from("extra-account-metas"), mint.toBuffer()],
+          hookProgramId
+        )[0],
+        launch,
+        launchPolicy,
+        globalStatePda,
+        transferRecord(mint, owner),
+        hookProgramId,
+      ].map((pubkey, i) => ({ pubkey, isSigner: false, isWritable: i === 4 }));
+
+    const vestingSchedule = (launch: PublicKey, beneficiary: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("vesting"), launch.toBuffer(), beneficiary.toBuffer()],
         program.programId
-      );
-      [bondingCurvePda] = PublicKey.findProgramAddressSync(
-        [Buffer.from("bonding"), seed],
+      )[0];
+
+    // Creates a launch on `tokenProgram` and returns its accounts. Anything in
+    // `overrides` replaces the defaults above; metadata defaults to empty
+    // unless the mint carries a metadata pointer to publish it.
+    const launchOn = async (
+      tokenProgram: PublicKey,
+      extensions: any,
+      overrides: {
+        curve?: any;
+        supply?: any;
+        graduation?: any;
+        allocations?: any[];
+        policy?: any;
+        metadata?: { name: string; symbol: string; uri: string };
+        creator?: Keypair;
+      } = {}
+    ) => {
+      const {
+        curve = { linear: { basePrice: new anchor.BN(basePrice), slope: new anchor.BN(slope) } },
+        allocations = [],
+        metadata = extensions.metadataPointer
+          ? { name: tokenName, symbol: tokenSymbol, uri: tokenUri }
+          : { name: "", symbol: "", uri: "" },
+        creator = adminKeypair,
+      } = overrides;
+      const { launchCount } = await program.account.globalState.fetch(globalStatePda);
+      const seed = launchCount.toArrayLike(Buffer, "le", 8);
+      const [launch] = PublicKey.findProgramAddressSync(
+        [Buffer.from("launch"), seed],
         program.programId
       );
-
-      tokenMint = await createMint(
-        connection,
-        adminKeypair,
-        adminKeypair.publicKey, // Change mint authority to adminKeypair initially
-        launchMetadataPda,     // Set freeze authority to launch metadata PDA
-        9 // TOKEN_DECIMALS
+      const mint = Keypair.generate();
+      const launchVault = getAssociatedTokenAddressSync(
+        mint.publicKey,
+        launch,
+        true,
+        tokenProgram
       );
-
-      const vaultAccount = await getOrCreateAssociatedTokenAccount(
-        connection,
-        adminKeypair,
-        tokenMint,
-        launchMetadataPda,
-        true
+      const [extraAccountMetaList] = PublicKey.findProgramAddressSync(
+        [Buffer.from("extra-account-metas"), mint.publicKey.toBuffer()],
+        hookProgramId
       );
-      vault = vaultAccount.address;
 
       await program.methods
         .createLaunch(
-          new anchor.BN(basePrice),
-          new anchor.BN(slope)
+          curve,
+          overrides.supply ?? supply,
+          overrides.graduation ?? graduation,
+          allocations,
+          overrides.policy ?? policy,
+          extensions,
+          metadata.name,
+          metadata.symbol,
+          metadata.uri
         )
-        .accounts({
+        .accountsPartial({
           globalState: globalStatePda,
-          launchMetadata: launchMetadataPda,
-          bondingCurve: bondingCurvePda,
-          tokenMint,
-          vault,
-          admin: adminKeypair.publicKey,
-          systemProgram: SystemProgram.programId,
-          tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
-          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
-          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          tokenMint: mint.publicKey,
+          vault: launchVault,
+          extraAccountMetaList: extensions.transferHook ? extraAccountMetaList : null,
+          transferHookProgram: extensions.transferHook ? hookProgramId : null,
+          creator: creator.publicKey,
+          platformFeeRecipient: adminKeypair.publicKey,
+          tokenProgram,
         })
-        .signers([adminKeypair])
+        .remainingAccounts(
+          allocations.flatMap(({ beneficiary }) => {
+            const schedule = vestingSchedule(launch, beneficiary);
+            return [
+              { pubkey: schedule, isSigner: false, isWritable: true },
+              {
+                pubkey: getAssociatedTokenAddressSync(mint.publicKey, schedule, true, tokenProgram),
+                isSigner: false,
+                isWritable: true,
+              },
+            ];
+          })
+        )
+        .signers([creator, mint])
         .rpc();
 
+      const [bondingCurve] = PublicKey.findProgramAddressSync(
+        [Buffer.from("bonding"), seed],
+        program.programId
+      );
+      const [solVault] = PublicKey.findProgramAddressSync(
+        [Buffer.from("sol_vault"), seed],
+        program.programId
+      );
+      const [launchPolicy] = PublicKey.findProgramAddressSync(
+        [Buffer.from("policy"), seed],
+        program.programId
+      );
+      return { launch, bondingCurve, launchPolicy, solVault, mint: mint.publicKey, vault: launchVault };
+    };
+
+    it("should create a new token launch successfully", async () => {
+      const { launch, bondingCurve, solVault, mint, vault } = await launchOn(
+        TOKEN_PROGRAM_ID,
+        noExtensions
+      );
+
       // Verify launch metadata
-      const launchMetadata = await program.account.launchMetadata.fetch(launchMetadataPda);
-      assert.ok(launchMetadata.tokenMint.equals(tokenMint), "Token mint not set correctly");
+      const launchMetadata = await program.account.launchMetadata.fetch(launch);
+      assert.ok(launchMetadata.tokenMint.equals(mint), "Token mint not set correctly");
       assert.ok(launchMetadata.vault.equals(vault), "Vault not set correctly");
       assert.equal(launchMetadata.launchId.toNumber(), 0, "Launch ID should be 0");
-      assert.equal(launchMetadata.currentDay.toNumber(), 0, "Current day should be 0");
+      assert.ok(launchMetadata.creator.equals(adminKeypair.publicKey), "Creator not recorded");
+
+      const globalState = await program.account.globalState.fetch(globalStatePda);
+      assert.equal(globalState.launchCount.toNumber(), 1, "Launch count should advance");
 
       // Verify bonding curve state
-      const bondingCurve = await program.account.bondingCurveState.fetch(bondingCurvePda);
-      assert.ok(bondingCurve.launchMetadata.equals(launchMetadataPda), "Launch metadata not set correctly");
-      assert.equal(bondingCurve.basePrice.toNumber(), basePrice, "Base price not set correctly");
-      assert.equal(bondingCurve.slope.toNumber(), slope, "Slope not set correctly");
-      assert.equal(bondingCurve.currentSupply.toNumber(), 0, "Current supply should be 0");
-      assert.equal(bondingCurve.decimals, 9, "Decimals should be 9");
-
-      // Verify token mint authority
-      const mintInfo = await getMint(connection, tokenMint);
-      assert.ok(mintInfo.mintAuthority === null, "Mint authority should be disabled");
-      assert.ok(mintInfo.freezeAuthority?.equals(launchMetadataPda), "Freeze authority not set correctly");
+      const curve = await program.account.bondingCurveState.fetch(bondingCurve);
+      assert.ok(curve.launchMetadata.equals(launch), "Launch metadata not set correctly");
+      assert.equal(curve.curve.linear.basePrice.toNumber(), basePrice, "Base price not set correctly");
+      assert.equal(curve.curve.linear.slope.toNumber(), slope, "Slope not set correctly");
+      assert.equal(curve.currentSupply.toNumber(), 0, "Current supply should be 0");
+      assert.equal(curve.decimals, 9, "Decimals should be 9");
+      assert.equal(curve.realSolReserves.toNumber(), 0, "Reserve should start empty");
+      assert.equal(curve.totalSupply.toString(), "1000000000000000000", "Total supply not recorded");
+      assert.equal(curve.saleSupply.toString(), "800000000000000000", "Sale supply should be 80%");
+
+      // Verify the SOL vault starts at its rent-exempt minimum
+      assert.equal(
+        await connection.getBalance(solVault),
+        await connection.getMinimumBalanceForRentExemption(0),
+        "SOL vault should hold only its rent"
+      );
+
+      // Verify mint authorities
+      const mintInfo = await getMint(connection, mint);
+      assert.ok(mintInfo.mintAuthority?.equals(launch), "Mint authority not set correctly");
+      assert.ok(mintInfo.freezeAuthority?.equals(launch), "Freeze authority not set correctly");
 
       // Verify vault
       const vaultInfo = await getAccount(connection, vault);
-      assert.ok(vaultInfo.amount.toString() === "1000000000000000000", "Initial supply not minted to vault");
-      assert.ok(vaultInfo.owner.equals(launchMetadataPda), "Vault owner not set correctly");
+      assert.equal(vaultInfo.amount.toString(), "1000000000000000000", "Initial supply not minted to vault");
+      assert.ok(vaultInfo.owner.equals(launch), "Vault owner not set correctly");
     });
 
-    it("should fail when unauthorized user tries to create launch", async () => {
-      const seed = new anchor.BN(1).toArrayLike(Buffer, "le", 8);
-      const [newLaunchPda] = PublicKey.findProgramAddressSync(
-        [Buffer.from("launch"), seed],
-        program.programId
-      );
-      const [newBondingPda] = PublicKey.findProgramAddressSync(
-        [Buffer.from("bonding"), seed],
-        program.programId
-      );
+    it("should let any wallet create a launch as its creator", async () => {
+      const { launch } = await launchOn(TOKEN_PROGRAM_ID, noExtensions, {
+        creator: unauthorizedUser,
+      });
 
-      const newMint = await createMint(
-        connection,
-        unauthorizedUser,
-        newLaunchPda,
-        null,
-        9
-      );
+      const launchMetadata = await program.account.launchMetadata.fetch(launch);
+      assert.ok(launchMetadata.creator.equals(unauthorizedUser.publicKey), "Creator not recorded");
+    });
 
-      const newVault = await getOrCreateAssociatedTokenAccount(
-        connection,
-        unauthorizedUser,
-        newMint,
-        newLaunchPda,
-        true
-      );
+    it("should fail when invalid parameters are provided", async () => {
+      try {
+        await launchOn(TOKEN_PROGRAM_ID, noExtensions, {
+          curve: { linear: { basePrice: new anchor.BN(0), slope: new anchor.BN(slope) } },
+        });
+        assert.fail("Should not allow invalid base price");
+      } catch (error) {
+        assert.include(error.message, "InvalidParams");
+      }
+    });
 
+    it("should fail when the policy exceeds the global bounds", async () => {
       try {
+        // default ceiling is 10%
+        await launchOn(TOKEN_PROGRAM_ID, noExtensions, { policy: { ...policy, sellLimitPercent: 50 } });
+        assert.fail("Should not allow a sell limit above the bound");
+      } catch (error) {
+        assert.include(error.message, "InvalidPolicy");
+      }
+    });
+
+    describe("Token Programs", () => {
+      for (const [name, tokenProgram] of [
+        ["the token program", TOKEN_PROGRAM_ID],
+        ["Token-2022", TOKEN_2022_PROGRAM_ID],
+      ] as const) {
+        it(`should create a plain launch on ${name}`, async () => {
+          const { launch, mint, vault } = await launchOn(tokenProgram, noExtensions);
+
+          const mintInfo = await getMint(connection, mint, undefined, tokenProgram);
+          assert.ok(mintInfo.mintAuthority?.equals(launch), "Mint authority not set correctly");
+          assert.equal(mintInfo.decimals, 9);
+
+          const vaultInfo = await getAccount(connection, vault, undefined, tokenProgram);
+          assert.equal(vaultInfo.amount.toString(), "1000000000000000000");
+          assert.ok(vaultInfo.owner.equals(launch), "Vault owner not set correctly");
+        });
+      }
+
+      it("should create a Token-2022 launch with every extension", async () => {
+        const { launch, mint } = await launchOn(TOKEN_2022_PROGRAM_ID, {
+          metadataPointer: true,
+          transferFee: { basisPoints: 100, maximumFee: new anchor.BN(1_000_000_000) },
+          transferHook: true,
+        });
+
+        const mintInfo = await getMint(connection, mint, undefined, TOKEN_2022_PROGRAM_ID);
+        const pointer = getMetadataPointerState(mintInfo);
+        assert.ok(pointer?.metadataAddress?.equals(mint), "Metadata should live on the mint");
+        assert.ok(pointer?.authority?.equals(launch));
+
+        const fee = getTransferFeeConfig(mintInfo);
+        assert.equal(fee?.newerTransferFee.transferFeeBasisPoints, 100);
+        assert.ok(fee?.withdrawWithheldAuthority.equals(adminKeypair.publicKey));
+
+        const hook = getTransferHook(mintInfo);
+        assert.ok(hook?.programId.equals(hookProgramId), "Transfer hook not set");
+
+        const metadata = await getTokenMetadata(connection, mint);
+        assert.equal(metadata?.name, tokenName);
+        assert.equal(metadata?.symbol, tokenSymbol);
+        assert.equal(metadata?.uri, tokenUri);
+        assert.ok(metadata?.updateAuthority?.equals(launch), "Launch should own the metadata");
+      });
+
+      it("should update the URI stored in the mint", async () => {
+        const { launch, mint } = await launchOn(TOKEN_2022_PROGRAM_ID, {
+          ...noExtensions,
+          metadataPointer: true,
+        });
+        const newUri = "https://example.com/sendor-v2-with-a-longer-path.json";
+
+        await program.methods
+          .updateTokenUri(newUri)
+          .accountsPartial({
+            launchMetadata: launch,
+            tokenMint: mint,
+            creator: adminKeypair.publicKey,
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+          })
+          .signers([adminKeypair])
+          .rpc();
+
+        const metadata = await getTokenMetadata(connection, mint);
+        assert.equal(metadata?.uri, newUri);
+        const launchMetadata = await program.account.launchMetadata.fetch(launch);
+        assert.equal(launchMetadata.tokenUri, newUri);
+      });
+
+      it("should create launches with maximum-length name, symbol and URI", async () => {
+        const metadata = {
+          name: "N".repeat(32),
+          symbol: "S".repeat(10),
+          uri: "https://example.com/" + "u".repeat(180),
+        };
+        const { launch, mint } = await launchOn(
+          TOKEN_2022_PROGRAM_ID,
+          { ...noExtensions, metadataPointer: true },
+          { metadata }
+        );
+
+        const launchMetadata = await program.account.launchMetadata.fetch(launch);
+        assert.equal(launchMetadata.tokenName, metadata.name);
+        assert.equal(launchMetadata.tokenSymbol, metadata.symbol);
+        assert.equal(launchMetadata.tokenUri, metadata.uri);
+        assert.equal((await getTokenMetadata(connection, mint))?.uri, metadata.uri);
+      });
+
+      it("should reject a name one byte over the maximum", async () => {
+        try {
+          await launchOn(TOKEN_2022_PROGRAM_ID, { ...noExtensions, metadataPointer: true }, {
+            metadata: { name: "N".repeat(33), symbol: tokenSymbol, uri: tokenUri },
+          });
+          assert.fail("Should not allow a 33-byte name");
+        } catch (error) {
+          assert.include(error.message, "InvalidParams");
+        }
+      });
+
+      it("should reject metadata on a mint without a metadata pointer", async () => {
+        try {
+          await launchOn(TOKEN_PROGRAM_ID, noExtensions, {
+            metadata: { name: tokenName, symbol: tokenSymbol, uri: tokenUri },
+          });
+          assert.fail("Should not store metadata no wallet can see");
+        } catch (error) {
+          assert.include(error.message, "MetadataWithoutPointer");
+        }
+      });
+
+      it("should reject Token-2022 extensions on the token program", async () => {
+        try {
+          await launchOn(TOKEN_PROGRAM_ID, { ...noExtensions, metadataPointer: true });
+          assert.fail("Should not allow extensions on a legacy mint");
+        } catch (error) {
+          assert.include(error.message, "UnsupportedExtension");
+        }
+      });
+
+      it("should reject a transfer fee above the maximum", async () => {
+        try {
+          await launchOn(TOKEN_2022_PROGRAM_ID, {
+            ...noExtensions,
+            transferFee: { basisPoints: 501, maximumFee: new anchor.BN(0) },
+          });
+          assert.fail("Should not allow a fee above 5%");
+        } catch (error) {
+          assert.include(error.message, "InvalidTransferFee");
+        }
+      });
+
+      it("should launch with a custom supply and decimals", async () => {
+        const custom = { totalSupply: new anchor.BN(21_000_000_000_000), decimals: 6, saleBps: 5000 };
+        const { bondingCurve, mint, vault } = await launchOn(TOKEN_PROGRAM_ID, noExtensions, {
+          supply: custom,
+          graduation: { supplySold: { 0: new anchor.BN(1_000_000) } },
+        });
+
+        const curve = await program.account.bondingCurveState.fetch(bondingCurve);
+        assert.equal(curve.decimals, 6);
+        assert.equal(curve.saleSupply.toNumber(), 10_500_000_000_000);
+        assert.equal((await getMint(connection, mint)).decimals, 6);
+        assert.equal((await getAccount(connection, vault)).amount.toString(), "21000000000000");
+      });
+
+      it("should reject allocations that eat into the sale supply", async () => {
+        try {
+          await launchOn(TOKEN_PROGRAM_ID, noExtensions, {
+            allocations: [
+              {
+                beneficiary: Keypair.generate().publicKey,
+                amount: new anchor.BN(1),
+                cliff: new anchor.BN(0),
+                duration: new anchor.BN(1),
+              },
+            ],
+            supply: { ...supply, saleBps: 10000 },
+          });
+          assert.fail("Should not vest tokens promised to the curve");
+        } catch (error) {
+          assert.include(error.message, "InvalidVesting");
+        }
+      });
+
+      it("should reject a zero graduation threshold", async () => {
+        try {
+          await launchOn(TOKEN_PROGRAM_ID, noExtensions, {
+            graduation: { solRaised: { 0: new anchor.BN(0) } },
+          });
+          assert.fail("Should not allow a launch that graduates immediately");
+        } catch (error) {
+          assert.include(error.message, "InvalidParams");
+        }
+      });
+
+      it("should reject a SOL threshold the curve can never raise", async () => {
+        try {
+          await launchOn(TOKEN_PROGRAM_ID, noExtensions, {
+            graduation: { solRaised: { 0: new anchor.BN("18446744073709551615") } },
+          });
+          assert.fail("Should not allow a launch that can never graduate");
+        } catch (error) {
+          assert.include(error.message, "InvalidParams");
+        }
+      });
+
+      it("should reject migration before the threshold is met", async () => {
+        const { launch, bondingCurve, mint, vault } = await launchOn(TOKEN_PROGRAM_ID, noExtensions);
+        const anyone = Keypair.generate();
+        await connection.confirmTransaction(
+          await connection.requestAirdrop(anyone.publicKey, anchor.web3.LAMPORTS_PER_SOL)
+        );
+
+        try {
+          await program.methods
+            .migrate()
+            .accountsPartial({
+              globalState: globalStatePda,
+              launchMetadata: launch,
+              bondingCurve,
+              vault,
+              tokenMint: mint,
+              creator: adminKeypair.publicKey,
+              poolTokenVault: Keypair.generate().publicKey,
+              lpMint: Keypair.generate().publicKey,
+              launchLpAccount: Keypair.generate().publicKey,
+              payer: anyone.publicKey,
+              tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .signers([anyone])
+            .rpc();
+          assert.fail("Should not migrate a launch with nothing sold");
+        } catch (error) {
+          assert.include(error.message, "PrematureMigration");
+        }
+      });
+
+      it("should count hooked transfers against the holder's allowance", async () => {
+        const holder = Keypair.generate();
+        const receiver = Keypair.generate();
+        await connection.confirmTransaction(
+          await connection.requestAirdrop(holder.publicKey, LAMPORTS_PER_SOL)
+        );
+        const vested = new anchor.BN(1_000_000_000);
+        const { launch, launchPolicy, mint } = await launchOn(
+          TOKEN_2022_PROGRAM_ID,
+          { ...noExtensions, transferHook: true },
+          {
+            allocations: [
+              { beneficiary: holder.publicKey, amount: vested, cliff: new anchor.BN(0), duration: new anchor.BN(1) },
+            ],
+          }
+        );
+
+        // One window spanning the whole day, drawn on the first transfer from
+        // the value revealed above.
         await program.methods
-          .createLaunch(
-            new anchor.BN(basePrice), 
-            new anchor.BN(slope)
+          .setWindowSchedule(1, new anchor.BN(86_400), new anchor.BN(86_400))
+          .accountsPartial({ globalState: globalStatePda, launchPolicy, admin: adminKeypair.publicKey })
+          .signers([adminKeypair])
+          .rpc();
+
+        // Let the allocation vest, then claim it.
+        await new Promise(resolve => setTimeout(resolve, 2000));
+        const schedule = vestingSchedule(launch, holder.publicKey);
+        const holderTokens = (
+          await getOrCreateAssociatedTokenAccount(
+            connection,
+            adminKeypair,
+            mint,
+            holder.publicKey,
+            false,
+            undefined,
+            undefined,
+            TOKEN_2022_PROGRAM_ID
           )
-          .accounts({
-            globalState: globalStatePda,
-            launchMetadata: newLaunchPda,
-            bondingCurve: newBondingPda,
-            tokenMint: newMint,
-            vault: newVault.address,
-            admin: unauthorizedUser.publicKey,
-            systemProgram: SystemProgram.programId,
-            tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
-            associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
-            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        ).address;
+        await program.methods
+          .claimVested()
+          .accountsPartial({
+            vestingSchedule: schedule,
+            tokenAccount: getAssociatedTokenAddressSync(mint, schedule, true, TOKEN_2022_PROGRAM_ID),
+            tokenMint: mint,
+            beneficiary: holder.publicKey,
+            beneficiaryTokenAccount: holderTokens,
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
           })
-          .signers([unauthorizedUser])
+          .remainingAccounts(hookAccounts(launch, launchPolicy, mint, schedule))
+          .signers([holder])
           .rpc();
-        assert.fail("Should not allow unauthorized user to create launch");
-      } catch (error) {
-        assert.include(error.message, "Constraint: Has One Failed");
-      }
-    });
 
-    it("should fail when invalid parameters are provided", async () => {
-      try {
+        const [userRecord] = PublicKey.findProgramAddressSync(
+          [Buffer.from("user"), launch.toBuffer(), holder.publicKey.toBuffer()],
+          program.programId
+        );
+        const allowance = (record: PublicKey | null) =>
+          program.methods
+            .getAllowance()
+            .accountsPartial({
+              launchMetadata: launch,
+              launchPolicy,
+              tokenMint: mint,
+              user: holder.publicKey,
+              userTokenAccount: holderTokens,
+              userRecord: record,
+              transferRecord: transferRecord(mint, holder.publicKey),
+            })
+            .view();
+
+        const before = await allowance(null);
+        // 20% of the snapshot balance
+        assert.equal(before.transferRemaining.toString(), "200000000");
+        const sent = new anchor.BN(50_000_000);
+
         await program.methods
-          .createLaunch(
-            new anchor.BN(0), 
-            new anchor.BN(slope)
-          ) // Invalid base price
-          .accounts({
+          .transfer(sent)
+          .accountsPartial({
             globalState: globalStatePda,
-            launchMetadata: launchMetadataPda,
-            bondingCurve: bondingCurvePda,
-            tokenMint,
-            vault,
-            admin: adminKeypair.publicKey,
-            systemProgram: SystemProgram.programId,
-            tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
-            associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
-            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+            launchMetadata: launch,
+            launchPolicy,
+            tokenMint: mint,
+            from: holder.publicKey,
+            sourceTokenAccount: holderTokens,
+            destinationTokenAccount: getAssociatedTokenAddressSync(
+              mint,
+              receiver.publicKey,
+              false,
+              TOKEN_2022_PROGRAM_ID
+            ),
+            to: receiver.publicKey,
+            transferRecord: transferRecord(mint, holder.publicKey),
+            transferHookProgram: hookProgramId,
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+          })
+          .remainingAccounts(hookAccounts(launch, launchPolicy, mint, holder.publicKey))
+          .signers([holder])
+          .rpc();
+
+        const after = await allowance(userRecord);
+        assert.equal(after.transferRemaining.toString(), before.transferRemaining.sub(sent).toString());
+        assert.equal(after.transferActionsRemaining, before.transferActionsRemaining - 1);
+      });
+
+      it("should vest an allocation and burn it on revoke", async () => {
+        const beneficiary = Keypair.generate();
+        const amount = new anchor.BN("100000000000000000"); // 10% of supply
+        const { launch, mint, vault } = await launchOn(TOKEN_PROGRAM_ID, noExtensions, {
+          allocations: [
+            {
+              beneficiary: beneficiary.publicKey,
+              amount,
+              cliff: new anchor.BN(3600),
+              duration: new anchor.BN(7200),
+            },
+          ],
+        });
+        const schedule = vestingSchedule(launch, beneficiary.publicKey);
+        const scheduleTokens = getAssociatedTokenAddressSync(mint, schedule, true);
+
+        const vesting = await program.account.vestingSchedule.fetch(schedule);
+        assert.ok(vesting.totalAmount.eq(amount));
+        assert.equal((await getAccount(connection, scheduleTokens)).amount.toString(), amount.toString());
+        assert.equal(
+          (await getAccount(connection, vault)).amount.toString(),
+          "900000000000000000",
+          "Vault should hold only the sale supply"
+        );
+
+        const beneficiaryTokens = await getOrCreateAssociatedTokenAccount(
+          connection,
+          adminKeypair,
+          mint,
+          beneficiary.publicKey
+        );
+        try {
+          await program.methods
+            .claimVested()
+            .accountsPartial({
+              vestingSchedule: schedule,
+              tokenAccount: scheduleTokens,
+              tokenMint: mint,
+              beneficiary: beneficiary.publicKey,
+              beneficiaryTokenAccount: beneficiaryTokens.address,
+              tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .signers([beneficiary])
+            .rpc();
+          assert.fail("Should not claim before the cliff");
+        } catch (error) {
+          assert.include(error.message, "NothingToClaim");
+        }
+
+        await program.methods
+          .revokeVesting()
+          .accountsPartial({
+            vestingSchedule: schedule,
+            tokenAccount: scheduleTokens,
+            tokenMint: mint,
+            authority: adminKeypair.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
           })
           .signers([adminKeypair])
           .rpc();
-        assert.fail("Should not allow invalid base price");
-      } catch (error) {
-        assert.include(error.message, "InvalidParams");
-      }
+
+        const revoked = await program.account.vestingSchedule.fetch(schedule);
+        assert.ok(revoked.revoked);
+        assert.equal(revoked.totalAmount.toNumber(), 0);
+        assert.equal((await getAccount(connection, scheduleTokens)).amount.toString(), "0");
+      });
     });
   });
 });